@@ -0,0 +1,103 @@
+// Copyright © 2014, Peter Atashian
+
+use matrix::{mod, Matrix3};
+use {Color3, ColorXyy, ColorXyz};
+
+/// The Hunt-Pointer-Estevez cone response matrix normalized to D65, used for von Kries.
+pub static HUNT_POINTER_ESTEVEZ: Matrix3 = [
+    [0.40024, 0.70760, -0.08081],
+    [-0.22630, 1.16532, 0.04570],
+    [0.00000, 0.00000, 0.91822],
+];
+
+pub static BRADFORD: Matrix3 = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+pub static CAT02: Matrix3 = [
+    [0.7328, 0.4296, -0.1624],
+    [-0.7036, 1.6975, 0.0061],
+    [0.0030, 0.0136, 0.9834],
+];
+
+pub static CAT16: Matrix3 = [
+    [0.401288, 0.650173, -0.051461],
+    [-0.250268, 1.204414, 0.045854],
+    [-0.002079, 0.048952, 0.953127],
+];
+
+#[deriving(Show, PartialEq)]
+pub enum AdaptationMethod {
+    /// Leaves tristimulus values untouched, so the white of the source is not mapped to the
+    /// white of the destination.
+    Identity,
+    XyzScaling,
+    VonKries,
+    Bradford,
+    Cat02,
+    Cat16,
+}
+
+impl AdaptationMethod {
+    /// The matrix from XYZ to the cone-like space in which the white points are scaled.
+    pub fn cone_matrix(&self) -> Matrix3 {
+        match *self {
+            AdaptationMethod::Identity | AdaptationMethod::XyzScaling => matrix::IDENTITY,
+            AdaptationMethod::VonKries => HUNT_POINTER_ESTEVEZ,
+            AdaptationMethod::Bradford => BRADFORD,
+            AdaptationMethod::Cat02 => CAT02,
+            AdaptationMethod::Cat16 => CAT16,
+        }
+    }
+}
+
+/// A chromatic adaptation from one white point to another as a single XYZ to XYZ matrix.
+#[deriving(Show)]
+pub struct ChromaticAdaptation {
+    pub matrix: Matrix3,
+}
+
+impl ChromaticAdaptation {
+    pub fn new(method: AdaptationMethod, src: &ColorXyz, dst: &ColorXyz) -> ChromaticAdaptation {
+        ChromaticAdaptation::partial(method, src, dst, 1.)
+    }
+    pub fn from_whites(method: AdaptationMethod, src: &ColorXyy<f64>, dst: &ColorXyy<f64>)
+                       -> ChromaticAdaptation {
+        ChromaticAdaptation::new(method, &src.to_xyz(), &dst.to_xyz())
+    }
+    /// Adapts with a degree of adaptation between 0 and 1. A degree of 0 only carries over the
+    /// change in luminance between the two whites, leaving chromaticities where they were.
+    pub fn partial(method: AdaptationMethod, src: &ColorXyz, dst: &ColorXyz, degree: f64)
+                   -> ChromaticAdaptation {
+        if method == AdaptationMethod::Identity {
+            return ChromaticAdaptation { matrix: matrix::IDENTITY }
+        }
+        let cone = method.cone_matrix();
+        let inv = matrix::invert(&cone).expect("cone matrix must be invertible");
+        let s = matrix::transform(&cone, &src.to_array());
+        let d = matrix::transform(&cone, &dst.to_array());
+        let (&Color3(_, sy, _), &Color3(_, dy, _)) = (src, dst);
+        let lum = dy / sy;
+        let gain = |i: uint| degree * d[i] / s[i] + (1. - degree) * lum;
+        let scale = matrix::diagonal(&[gain(0), gain(1), gain(2)]);
+        ChromaticAdaptation {
+            matrix: matrix::mul(&inv, &matrix::mul(&scale, &cone)),
+        }
+    }
+    pub fn inverse(&self) -> ChromaticAdaptation {
+        ChromaticAdaptation {
+            matrix: matrix::invert(&self.matrix).expect("adaptation matrix must be invertible"),
+        }
+    }
+    pub fn apply(&self, c: &ColorXyz) -> ColorXyz {
+        Color3::from_array(&matrix::transform(&self.matrix, &c.to_array()))
+    }
+}
+
+/// The degree of adaptation of CIECAM02 and CAM16 for a surround factor `f` (1 for average,
+/// 0.9 for dim, 0.8 for dark) and an adapting luminance in cd/m².
+pub fn degree_of_adaptation(f: f64, adapting_luminance: f64) -> f64 {
+    (f * (1. - (1. / 3.6) * ((-adapting_luminance - 42.) / 92.).exp())).min(1.).max(0.)
+}
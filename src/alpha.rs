@@ -0,0 +1,74 @@
+// Copyright © 2014, Peter Atashian
+
+use std::num::{Zero};
+use component::Component;
+use unit::{Encoded, Linear, Space};
+use Color3;
+
+/// Alpha units for `ColorAlpha`.
+#[deriving(Show)]
+pub struct Straight;
+#[deriving(Show)]
+pub struct Premultiplied;
+
+/// A `Color3` with an alpha channel, where `A` records whether the color is premultiplied.
+#[deriving(Show)]
+pub struct ColorAlpha<T, U, A>(pub Color3<T, U>, pub T);
+
+pub type ColorRgbaU8<U> = ColorAlpha<u8, U, Straight>;
+/// RGB with straight alpha, where the color channels do not include the coverage.
+pub type ColorRgbaF64<U> = ColorAlpha<f64, U, Straight>;
+/// RGB with premultiplied alpha, where the color channels are already scaled by the coverage.
+pub type ColorRgbaPremulF64<U> = ColorAlpha<f64, U, Premultiplied>;
+
+impl<T, U, A> ColorAlpha<T, U, A> where T: Copy {
+    pub fn color(&self) -> Color3<T, U> {
+        let &ColorAlpha(Color3(r, g, b), _) = self;
+        Color3(r, g, b)
+    }
+    pub fn alpha(&self) -> T {
+        let &ColorAlpha(_, a) = self;
+        a
+    }
+}
+
+impl<T, U, A> ColorAlpha<T, U, A> where T: Component {
+    /// Converts to another component type, alpha included.
+    pub fn cast<V>(&self) -> ColorAlpha<V, U, A> where V: Component {
+        let &ColorAlpha(ref c, a) = self;
+        ColorAlpha(c.cast(), Component::from_unit(a.to_unit()))
+    }
+}
+
+impl<T, U> ColorAlpha<T, U, Straight> where T: Float {
+    pub fn premultiply(&self) -> ColorAlpha<T, U, Premultiplied> {
+        let &ColorAlpha(Color3(r, g, b), a) = self;
+        ColorAlpha(Color3(r * a, g * a, b * a), a)
+    }
+}
+
+impl<T, U> ColorAlpha<T, U, Premultiplied> where T: Float {
+    /// A fully transparent color has no color left to recover and comes back as transparent
+    /// black.
+    pub fn unpremultiply(&self) -> ColorAlpha<T, U, Straight> {
+        let &ColorAlpha(Color3(r, g, b), a) = self;
+        if a.is_zero() {
+            return ColorAlpha(Color3(a, a, a), a)
+        }
+        ColorAlpha(Color3(r / a, g / a, b / a), a)
+    }
+}
+
+impl<S> ColorAlpha<f64, Linear<S>, Straight> where S: Space {
+    pub fn encode(&self) -> ColorAlpha<f64, Encoded<S>, Straight> {
+        let &ColorAlpha(ref c, a) = self;
+        ColorAlpha(c.encode(), a)
+    }
+}
+
+impl<S> ColorAlpha<f64, Encoded<S>, Straight> where S: Space {
+    pub fn decode(&self) -> ColorAlpha<f64, Linear<S>, Straight> {
+        let &ColorAlpha(ref c, a) = self;
+        ColorAlpha(c.decode(), a)
+    }
+}
@@ -5,8 +5,14 @@
 extern crate colors;
 
 use std::iter::{AdditiveIterator};
-use colors::{ColorXyz, SRGB, ColorRgbF64, ColorRgbU8};
+use colors::{Color3, ColorXyz};
+use colors::oklab::ColorOklch;
 use colors::tables::CIE_COLOR_MATCH;
+use colors::unit::{Encoded, Linear, Srgb};
+
+fn to_u8(c: &Color3<f64, Linear<Srgb>>) -> Color3<u8, Encoded<Srgb>> {
+    c.encode().cast()
+}
 
 fn rainbow_username() {
     let s = "ABCDEFGHI";
@@ -15,26 +21,38 @@ fn rainbow_username() {
     let lo = 400;
     let hi = 650;
     let colors = range(0, len).map(|i| {
-        let xyz = ColorXyz::from_wavelength(lo + (num - i) * (hi - lo) / num);
-        (xyz.to_rgb(&SRGB).constrain().normalize() + ColorRgbF64::white() * 0.1).normalize()
-    }).collect::<Vec<ColorRgbF64>>();
+        let xyz: ColorXyz = Color3::from_wavelength(lo + (num - i) * (hi - lo) / num);
+        let white: Color3<f64, Linear<Srgb>> = Color3::white();
+        (xyz.to_linear::<Srgb>().constrain().normalize() + white * 0.1).normalize()
+    }).collect::<Vec<Color3<f64, Linear<Srgb>>>>();
     let minlum = colors.iter().fold(1f64, |s, c| {
-        s.min(c.luminance(&SRGB))
+        s.min(c.luminance())
     });
-    for c in colors.iter().map(|c| {
-        (c * minlum / c.luminance(&SRGB)).encode_srgb().to_int()
+    for Color3(r, g, b) in colors.iter().map(|c| {
+        to_u8(&(*c * minlum / c.luminance()))
     }) {
-        println!("{:02X}{:02X}{:02X}", c.r, c.g, c.b);
+        println!("{:02X}{:02X}{:02X}", r, g, b);
     }
 }
 
 fn irc_nick_colors() {
     let num = 9u;
-    for c in range(0, num).map(|i| {
+    for Color3(r, g, b) in range(0, num).map(|i| {
         let hue = i as f64 * (6. / num as f64);
-        ColorRgbF64::from_hue(hue).target_luminance(0.5, &SRGB).encode_srgb().to_int()
+        let c: Color3<f64, Linear<Srgb>> = Color3::from_hue(hue);
+        to_u8(&c.target_luminance(0.5))
+    }) {
+        println!("{:02X}{:02X}{:02X}", r, g, b);
+    }
+}
+
+fn oklch_nick_colors() {
+    let num = 9u;
+    for Color3(r, g, b) in range(0, num).map(|i| {
+        let hue = i as f64 * (360. / num as f64);
+        to_u8(&ColorOklch { l: 0.65, c: 0.1, h: hue }.to_rgb())
     }) {
-        println!("{:02X}{:02X}{:02X}", c.r, c.g, c.b);
+        println!("{:02X}{:02X}{:02X}", r, g, b);
     }
 }
 
@@ -48,15 +66,16 @@ fn black_body(temp: f64) -> ColorXyz {
     }
     CIE_COLOR_MATCH.iter().enumerate().map(|(wave, col)| {
         let energy = sample((wave as f64 + 390.) * 1E-9, temp);
-        ColorXyz::from_array(col) * energy
+        let c: ColorXyz = Color3::from_array(col);
+        c * energy
     }).sum()
 }
 
 fn stuff() {
     for i in range(0i, 14) {
         let i = i as f64 * 100. + 1000.;
-        let c = black_body(i).to_rgb(&SRGB).constrain().normalize().encode_srgb().to_int();
-        println!("{:02X}{:02X}{:02X}", c.r, c.g, c.b);
+        let Color3(r, g, b) = to_u8(&black_body(i).to_linear::<Srgb>().constrain().normalize());
+        println!("{:02X}{:02X}{:02X}", r, g, b);
     }
 }
 
@@ -65,18 +84,21 @@ fn wikipedia() {
     let target = 0.2;
     for i in range(0, num) {
         let hue = i as f64 * (6. / num as f64);
-        let c = ColorRgbF64::from_hue(hue).target_luminance(target, &SRGB).encode_srgb().to_int();
-        println!("{:02X}{:02X}{:02X}", c.r, c.g, c.b);
+        let c: Color3<f64, Linear<Srgb>> = Color3::from_hue(hue);
+        let Color3(r, g, b) = to_u8(&c.target_luminance(target));
+        println!("{:02X}{:02X}{:02X}", r, g, b);
     }
-    let c = ColorRgbF64::white().target_luminance(target, &SRGB).encode_srgb().to_int();
-    println!("{:02X}{:02X}{:02X}", c.r, c.g, c.b);
+    let c: Color3<f64, Linear<Srgb>> = Color3::white();
+    let Color3(r, g, b) = to_u8(&c.target_luminance(target));
+    println!("{:02X}{:02X}{:02X}", r, g, b);
 }
 
 fn grayscale(c: u32) -> f64 {
     let r = (c >> 16) as u8;
     let g = (c >> 8) as u8;
     let b = (c >> 0) as u8;
-    ColorRgbU8 { r: r, g: g, b: b }.to_float().decode_srgb().luminance(&SRGB)
+    let c: Color3<u8, Encoded<Srgb>> = Color3(r, g, b);
+    c.cast::<f64>().decode().luminance()
 }
 
 fn print_colors() {
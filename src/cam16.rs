@@ -0,0 +1,276 @@
+// Copyright © 2014, Peter Atashian
+
+use adaptation::{CAT16, degree_of_adaptation};
+use component::Component;
+use matrix::{mod, Matrix3};
+use {Color3, ColorXyz};
+
+pub static CAT16_INVERSE: Matrix3 = [
+    [1.8620678551, -1.0112546305, 0.1491867754],
+    [0.3875265432, 0.6214474419, -0.0089739852],
+    [-0.0158414988, -0.0341229380, 1.0499644369],
+];
+
+#[deriving(Show, PartialEq)]
+pub enum Surround {
+    Average,
+    Dim,
+    Dark,
+}
+
+impl Surround {
+    /// The factor F, the impact of the surround c and the chromatic induction factor Nc.
+    pub fn parameters(&self) -> (f64, f64, f64) {
+        match *self {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+/// The viewing conditions of CAM16 along with everything derived from them that does not
+/// depend on the stimulus.
+#[deriving(Show)]
+pub struct ViewingConditions {
+    /// The adopted white, with a luminance of 100.
+    pub white: ColorXyz,
+    /// The factor taking colors on the scale of the given white to a white luminance of 100.
+    pub scale: f64,
+    /// The adapting luminance in cd/m², usually a fifth of the luminance of the white.
+    pub adapting_luminance: f64,
+    /// The relative luminance of the background, where the white is 100.
+    pub background: f64,
+    pub surround: Surround,
+    pub c: f64,
+    pub nc: f64,
+    pub fl: f64,
+    pub n: f64,
+    pub z: f64,
+    pub nbb: f64,
+    pub ncb: f64,
+    pub d_rgb: [f64, ..3],
+    pub aw: f64,
+}
+
+impl ViewingConditions {
+    /// Takes the white on the same scale as the colors to be converted, such as a luminance of 1
+    /// for the whites of this crate.
+    pub fn new(white: &ColorXyz, adapting_luminance: f64, background: f64, surround: Surround)
+               -> ViewingConditions {
+        let &Color3(_, wy, _) = white;
+        let scale = 100. / wy;
+        let white = *white * scale;
+        let Color3(_, wy, _) = white;
+        let (f, c, nc) = surround.parameters();
+        let la5 = 5. * adapting_luminance;
+        let k = 1. / (la5 + 1.);
+        let k4 = k.powi(4);
+        let fl = 0.2 * k4 * la5 + 0.1 * (1. - k4).powi(2) * la5.cbrt();
+        let n = background / wy;
+        let z = 1.48 + n.sqrt();
+        let nbb = 0.725 * n.powf(-0.2);
+        let d = degree_of_adaptation(f, adapting_luminance);
+        let rgbw = matrix::transform(&CAT16, &white.to_array());
+        let d_rgb = [
+            d * wy / rgbw[0] + 1. - d,
+            d * wy / rgbw[1] + 1. - d,
+            d * wy / rgbw[2] + 1. - d,
+        ];
+        let mut vc = ViewingConditions {
+            white: white,
+            scale: scale,
+            adapting_luminance: adapting_luminance,
+            background: background,
+            surround: surround,
+            c: c,
+            nc: nc,
+            fl: fl,
+            n: n,
+            z: z,
+            nbb: nbb,
+            ncb: nbb,
+            d_rgb: d_rgb,
+            aw: 0.,
+        };
+        let a = vc.compress(&[d_rgb[0] * rgbw[0], d_rgb[1] * rgbw[1], d_rgb[2] * rgbw[2]]);
+        vc.aw = (2. * a[0] + a[1] + 0.05 * a[2] - 0.305) * nbb;
+        vc
+    }
+    fn compress(&self, rgb: &[f64, ..3]) -> [f64, ..3] {
+        let f = |x: f64| {
+            let p = (self.fl * x.abs() / 100.).powf(0.42);
+            400. * x.signum() * p / (p + 27.13) + 0.1
+        };
+        [f(rgb[0]), f(rgb[1]), f(rgb[2])]
+    }
+    fn expand(&self, rgb: &[f64, ..3]) -> [f64, ..3] {
+        let f = |x: f64| {
+            let x = x - 0.1;
+            x.signum() * 100. / self.fl * (27.13 * x.abs() / (400. - x.abs())).powf(0.42f64.recip())
+        };
+        [f(rgb[0]), f(rgb[1]), f(rgb[2])]
+    }
+}
+
+/// The correlates of the CAM16 color appearance model: lightness J, chroma C, hue angle h in
+/// degrees, brightness Q, colorfulness M and saturation s.
+#[deriving(Show)]
+pub struct ColorCam16<T> {
+    pub j: T,
+    pub c: T,
+    pub h: T,
+    pub q: T,
+    pub m: T,
+    pub s: T,
+}
+
+components!(ColorCam16 { j, c, h, q, m, s })
+
+impl ColorCam16<f64> {
+    pub fn from_xyz(xyz: &ColorXyz, vc: &ViewingConditions) -> ColorCam16<f64> {
+        let rgb = matrix::transform(&CAT16, &(*xyz * vc.scale).to_array());
+        let d = vc.d_rgb;
+        let ra = vc.compress(&[d[0] * rgb[0], d[1] * rgb[1], d[2] * rgb[2]]);
+        let a = ra[0] - 12. * ra[1] / 11. + ra[2] / 11.;
+        let b = (ra[0] + ra[1] - 2. * ra[2]) / 9.;
+        let (_, h) = ::to_polar(a, b);
+        let et = 0.25 * ((h.to_radians() + 2.).cos() + 3.8);
+        let aa = (2. * ra[0] + ra[1] + 0.05 * ra[2] - 0.305) * vc.nbb;
+        let j = 100. * (aa / vc.aw).powf(vc.c * vc.z);
+        let q = (4. / vc.c) * (j / 100.).sqrt() * (vc.aw + 4.) * vc.fl.powf(0.25);
+        let t = (50000. / 13. * vc.nc * vc.ncb * et * a.hypot(b))
+            / (ra[0] + ra[1] + 21. / 20. * ra[2]);
+        let c = t.powf(0.9) * (j / 100.).sqrt() * (1.64 - 0.29f64.powf(vc.n)).powf(0.73);
+        let m = c * vc.fl.powf(0.25);
+        ColorCam16 {
+            j: j,
+            c: c,
+            h: h,
+            q: q,
+            m: m,
+            s: if q == 0. { 0. } else { 100. * (m / q).sqrt() },
+        }
+    }
+    /// Recovers XYZ from the lightness, chroma and hue correlates.
+    pub fn to_xyz(&self, vc: &ViewingConditions) -> ColorXyz {
+        let (j, c) = (self.j, self.c);
+        let t = if j <= 0. {
+            0.
+        } else {
+            (c / ((j / 100.).sqrt() * (1.64 - 0.29f64.powf(vc.n)).powf(0.73))).powf(0.9f64.recip())
+        };
+        let hr = self.h.to_radians();
+        let et = 0.25 * ((hr + 2.).cos() + 3.8);
+        let aa = vc.aw * (j / 100.).powf((vc.c * vc.z).recip());
+        let p2 = aa / vc.nbb + 0.305;
+        let (a, b) = if t == 0. {
+            (0., 0.)
+        } else {
+            let p1 = 50000. / 13. * vc.nc * vc.ncb * et / t;
+            let p3 = 21. / 20.;
+            let (hs, hc) = hr.sin_cos();
+            if hs.abs() >= hc.abs() {
+                let p4 = p1 / hs;
+                let b = p2 * (2. + p3) * (460. / 1403.)
+                    / (p4 + (2. + p3) * (220. / 1403.) * (hc / hs) - 27. / 1403.
+                       + p3 * (6300. / 1403.));
+                (b * hc / hs, b)
+            } else {
+                let p5 = p1 / hc;
+                let a = p2 * (2. + p3) * (460. / 1403.)
+                    / (p5 + (2. + p3) * (220. / 1403.)
+                       - (27. / 1403. - p3 * (6300. / 1403.)) * (hs / hc));
+                (a, a * hs / hc)
+            }
+        };
+        let ra = [
+            (460. * p2 + 451. * a + 288. * b) / 1403.,
+            (460. * p2 - 891. * a - 261. * b) / 1403.,
+            (460. * p2 - 220. * a - 6300. * b) / 1403.,
+        ];
+        let rc = vc.expand(&ra);
+        let d = vc.d_rgb;
+        let rgb = [rc[0] / d[0], rc[1] / d[1], rc[2] / d[2]];
+        Color3::from_array(&matrix::transform(&CAT16_INVERSE, &rgb)) * vc.scale.recip()
+    }
+}
+
+/// CAM16-UCS, the uniform color space J'a'b' built on the CAM16 lightness and colorfulness.
+#[deriving(Show)]
+pub struct ColorCam16Ucs<T> {
+    pub j: T,
+    pub a: T,
+    pub b: T,
+}
+
+components!(ColorCam16Ucs { j, a, b })
+
+impl ColorCam16Ucs<f64> {
+    pub fn from_cam16(c: &ColorCam16<f64>) -> ColorCam16Ucs<f64> {
+        let m = (1. + 0.0228 * c.m).ln() / 0.0228;
+        let (a, b) = ::from_polar(m, c.h);
+        ColorCam16Ucs {
+            j: 1.7 * c.j / (1. + 0.007 * c.j),
+            a: a,
+            b: b,
+        }
+    }
+    pub fn to_cam16(&self, vc: &ViewingConditions) -> ColorCam16<f64> {
+        let (m, h) = ::to_polar(self.a, self.b);
+        let m = ((0.0228 * m).exp() - 1.) / 0.0228;
+        let j = self.j / (1.7 - 0.007 * self.j);
+        let q = (4. / vc.c) * (j / 100.).sqrt() * (vc.aw + 4.) * vc.fl.powf(0.25);
+        ColorCam16 {
+            j: j,
+            c: m / vc.fl.powf(0.25),
+            h: h,
+            q: q,
+            m: m,
+            s: if q == 0. { 0. } else { 100. * (m / q).sqrt() },
+        }
+    }
+    pub fn from_xyz(xyz: &ColorXyz, vc: &ViewingConditions) -> ColorCam16Ucs<f64> {
+        ColorCam16Ucs::from_cam16(&ColorCam16::from_xyz(xyz, vc))
+    }
+    pub fn to_xyz(&self, vc: &ViewingConditions) -> ColorXyz {
+        self.to_cam16(vc).to_xyz(vc)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {Color3, ColorXyz, D65};
+    use super::{ColorCam16, Surround, ViewingConditions};
+
+    static SAMPLES: [[f64, ..3], ..3] = [[0.2, 0.15, 0.1], [0.05, 0.1, 0.4], [0.9, 0.95, 1.0]];
+
+    fn conditions(scale: f64) -> ViewingConditions {
+        ViewingConditions::new(&(D65.to_xyz() * scale), 40., 20., Surround::Average)
+    }
+
+    #[test]
+    fn round_trip() {
+        for &scale in [1., 100.].iter() {
+            let vc = conditions(scale);
+            for c in SAMPLES.iter() {
+                let xyz: ColorXyz = Color3::from_array(c) * scale;
+                let back = ColorCam16::from_xyz(&xyz, &vc).to_xyz(&vc);
+                for (a, b) in xyz.to_array().iter().zip(back.to_array().iter()) {
+                    assert!((*a - *b).abs() < 1e-8 * scale, "{} {}", xyz, back);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn white_scale() {
+        let (vc1, vc100) = (conditions(1.), conditions(100.));
+        for c in SAMPLES.iter() {
+            let xyz: ColorXyz = Color3::from_array(c);
+            let a = ColorCam16::from_xyz(&xyz, &vc1);
+            let b = ColorCam16::from_xyz(&(xyz * 100.), &vc100);
+            assert!((a.j - b.j).abs() < 1e-9 && (a.c - b.c).abs() < 1e-9, "{} {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright © 2014, Peter Atashian
+
+//! Naive device CMYK, where inks are treated as ideal filters over encoded sRGB. This is not a
+//! profile-based separation and will not match a press; it only gives plausible ink values.
+
+use component::Component;
+use unit::{Encoded, Srgb};
+use Color3;
+
+#[deriving(Show, PartialEq)]
+pub enum BlackGeneration {
+    /// Under color removal, where black only replaces the gray component of neutral colors and
+    /// fades out as colors become more saturated.
+    Ucr,
+    /// Gray component replacement, where black replaces the gray component of every color.
+    Gcr,
+}
+
+/// How black is generated and how much ink may be laid down in total.
+#[deriving(Show)]
+pub struct Separation {
+    pub black: BlackGeneration,
+    /// How much of the gray component is replaced by black, from 0 to 1.
+    pub amount: f64,
+    /// The gray component below which no black is generated, from 0 to 1.
+    pub start: f64,
+    /// The total area coverage limit as the sum of all four inks, such as 3 for 300%.
+    pub ink_limit: f64,
+}
+
+pub static FULL_GCR: Separation = Separation {
+    black: BlackGeneration::Gcr,
+    amount: 1.,
+    start: 0.,
+    ink_limit: 4.,
+};
+
+/// Ink coverages from 0 to 1.
+#[deriving(Show)]
+pub struct ColorCmyk<T> {
+    pub c: T,
+    pub m: T,
+    pub y: T,
+    pub k: T,
+}
+
+components!(ColorCmyk { c, m, y, k })
+
+impl ColorCmyk<f64> {
+    /// Separates encoded sRGB of any component type.
+    pub fn from_rgb<T>(rgb: &Color3<T, Encoded<Srgb>>, sep: &Separation) -> ColorCmyk<f64>
+                       where T: Component {
+        let Color3(r, g, b) = rgb.cast::<f64>();
+        let (c, m, y) = (1. - r.min(1.).max(0.), 1. - g.min(1.).max(0.), 1. - b.min(1.).max(0.));
+        let gray = c.min(m).min(y);
+        let neutral = match sep.black {
+            BlackGeneration::Ucr => 1. - (c.max(m).max(y) - gray),
+            BlackGeneration::Gcr => 1.,
+        };
+        let k = if gray > sep.start && sep.start < 1. {
+            sep.amount * neutral * (gray - sep.start) / (1. - sep.start)
+        } else {
+            0.
+        };
+        let under = |x: f64| if k < 1. { (x - k) / (1. - k) } else { 0. };
+        let (c, m, y) = (under(c), under(m), under(y));
+        let total = c + m + y + k;
+        if total <= sep.ink_limit {
+            return ColorCmyk { c: c, m: m, y: y, k: k }
+        }
+        // Keep as much black as allowed and scale the colored inks into what remains.
+        let k = k.min(sep.ink_limit);
+        let cmy = c + m + y;
+        let scale = if cmy > 0. { (sep.ink_limit - k) / cmy } else { 0. };
+        ColorCmyk {
+            c: c * scale,
+            m: m * scale,
+            y: y * scale,
+            k: k,
+        }
+    }
+    /// Converts back to encoded sRGB. Colors that were limited by the ink limit come back
+    /// lighter than they went in.
+    pub fn to_rgb<T>(&self) -> Color3<T, Encoded<Srgb>> where T: Component {
+        let w = 1. - self.k;
+        let rgb: Color3<f64, Encoded<Srgb>> =
+            Color3((1. - self.c) * w, (1. - self.m) * w, (1. - self.y) * w);
+        rgb.cast()
+    }
+    pub fn total(&self) -> f64 {
+        self.c + self.m + self.y + self.k
+    }
+}
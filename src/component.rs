@@ -0,0 +1,153 @@
+// Copyright © 2014, Peter Atashian
+
+//! Scalar types that color channels can be stored as. Floats hold values as they are, while
+//! integers map their full range onto [0, 1]. Going through `f64` is lossless for every type
+//! here, so widening never loses precision and narrowing rounds to the nearest value.
+//!
+//! `Color3` and every color model take their component type as a parameter and convert between
+//! types with `cast`. Conversions between models are done on `f64` components.
+
+pub trait Component: Copy {
+    fn to_unit(self) -> f64;
+    /// Rounds to the nearest representable value, clamping integers to [0, 1].
+    fn from_unit(x: f64) -> Self;
+}
+
+impl Component for f64 {
+    fn to_unit(self) -> f64 {
+        self
+    }
+    fn from_unit(x: f64) -> f64 {
+        x
+    }
+}
+
+impl Component for f32 {
+    fn to_unit(self) -> f64 {
+        self as f64
+    }
+    fn from_unit(x: f64) -> f32 {
+        x as f32
+    }
+}
+
+impl Component for u8 {
+    fn to_unit(self) -> f64 {
+        self as f64 / 255.
+    }
+    fn from_unit(x: f64) -> u8 {
+        (x.min(1.).max(0.) * 255.).round() as u8
+    }
+}
+
+impl Component for u16 {
+    fn to_unit(self) -> f64 {
+        self as f64 / 65535.
+    }
+    fn from_unit(x: f64) -> u16 {
+        (x.min(1.).max(0.) * 65535.).round() as u16
+    }
+}
+
+/// An IEEE 754 binary16 half precision float, stored as its bits.
+#[deriving(Show, PartialEq)]
+pub struct Half(pub u16);
+
+fn round_even(x: f64) -> f64 {
+    let r = x.round();
+    if (r - x).abs() == 0.5 && r % 2. != 0. { r - x.signum() } else { r }
+}
+
+impl Component for Half {
+    fn to_unit(self) -> f64 {
+        let Half(bits) = self;
+        let sign = if bits & 0x8000 != 0 { -1. } else { 1. };
+        let exp = ((bits >> 10) & 0x1f) as int;
+        let mant = (bits & 0x3ff) as f64;
+        sign * match exp {
+            0 => mant * 2f64.powi(-24),
+            0x1f if mant == 0. => Float::infinity(),
+            0x1f => Float::nan(),
+            _ => (1. + mant / 1024.) * 2f64.powi(exp as i32 - 15),
+        }
+    }
+    fn from_unit(x: f64) -> Half {
+        if x.is_nan() {
+            return Half(0x7e00)
+        }
+        let sign = if x.is_sign_negative() { 0x8000u16 } else { 0 };
+        if x.is_infinite() {
+            return Half(sign | 0x7c00)
+        }
+        let a = x.abs();
+        if a < 2f64.powi(-14) {
+            // Subnormals share one exponent, and rounding up to 0x400 lands on the smallest
+            // normal value.
+            return Half(sign | round_even(a * 2f64.powi(24)) as u16)
+        }
+        let mut e = a.log2().floor() as i32;
+        if 2f64.powi(e) > a {
+            e -= 1;
+        } else if 2f64.powi(e + 1) <= a {
+            e += 1;
+        }
+        let mut mant = round_even(a * 2f64.powi(10 - e));
+        if mant >= 2048. {
+            mant /= 2.;
+            e += 1;
+        }
+        if e > 15 {
+            return Half(sign | 0x7c00)
+        }
+        Half(sign | ((e + 15) as u16) << 10 | (mant as u16 - 1024))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Component, Half};
+
+    #[test]
+    fn integer_round_trip() {
+        for v in range(0u, 256) {
+            assert_eq!(Component::from_unit((v as u8).to_unit()), v as u8);
+        }
+        for v in range(0u, 65536) {
+            assert_eq!(Component::from_unit((v as u16).to_unit()), v as u16);
+        }
+    }
+
+    #[test]
+    fn half_round_trip() {
+        for bits in range(0u, 65536).map(|b| b as u16) {
+            let x = Half(bits).to_unit();
+            if bits & 0x7c00 == 0x7c00 && bits & 0x3ff != 0 {
+                assert!(x.is_nan());
+            } else {
+                assert_eq!(Component::from_unit(x), Half(bits));
+            }
+        }
+    }
+
+    #[test]
+    fn half_rounding() {
+        let half = |x: f64| -> Half { Component::from_unit(x) };
+        // Subnormals, with ties going to the even neighbor and the top one carrying into the
+        // smallest normal value.
+        assert_eq!(Half(1).to_unit(), 2f64.powi(-24));
+        assert_eq!(half(2f64.powi(-25)), Half(0));
+        assert_eq!(half(3. * 2f64.powi(-25)), Half(2));
+        assert_eq!(half(2f64.powi(-14) - 2f64.powi(-25)), Half(0x400));
+        // Normals, where ties also go to even.
+        assert_eq!(half(1. + 2f64.powi(-11)), Half(0x3c00));
+        assert_eq!(half(1. + 3. * 2f64.powi(-11)), Half(0x3c02));
+        // The largest finite value is 65504, and the halfway point to 65536 overflows.
+        assert_eq!(half(65519.), Half(0x7bff));
+        assert_eq!(half(65520.), Half(0x7c00));
+        assert_eq!(half(-65520.), Half(0xfc00));
+        assert!(half(Float::nan()).to_unit().is_nan());
+        let zero = half(-0.);
+        assert_eq!(zero, Half(0x8000));
+        assert!(zero.to_unit() == 0. && zero.to_unit().is_sign_negative());
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright © 2014, Peter Atashian
+
+//! Porter-Duff compositing and the blend modes of the W3C Compositing and Blending spec.
+
+use alpha::{ColorAlpha, ColorRgbaF64, ColorRgbaPremulF64};
+use unit::{Encoded, Srgb};
+use Color3;
+
+/// Porter-Duff operators, each combining a source with the destination beneath it.
+#[deriving(Show, PartialEq)]
+pub enum Operator {
+    Over,
+    In,
+    Out,
+    Atop,
+    Xor,
+}
+
+/// Composites premultiplied colors. The operator only weighs the inputs, so they may be in
+/// linear light or encoded, as long as both are the same.
+pub fn composite<U>(src: &ColorRgbaPremulF64<U>, dst: &ColorRgbaPremulF64<U>, op: Operator)
+                    -> ColorRgbaPremulF64<U> {
+    let (&ColorAlpha(s, sa), &ColorAlpha(d, da)) = (src, dst);
+    let (fa, fb) = match op {
+        Operator::Over => (1., 1. - sa),
+        Operator::In => (da, 0.),
+        Operator::Out => (1. - da, 0.),
+        Operator::Atop => (da, 1. - sa),
+        Operator::Xor => (1. - da, 1. - sa),
+    };
+    ColorAlpha(s * fa + d * fb, sa * fa + da * fb)
+}
+
+#[deriving(Show, PartialEq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+/// Where blending happens. Browsers blend encoded sRGB values directly, while blending in
+/// linear light matches how light actually mixes.
+#[deriving(Show, PartialEq)]
+pub enum BlendSpace {
+    Srgb,
+    Linear,
+}
+
+impl BlendSpace {
+    /// The luma weights used by the non-separable modes. The spec's weights are meant for
+    /// encoded values, so linear light uses the luminance of the sRGB primaries instead.
+    fn weights(&self) -> [f64, ..3] {
+        match *self {
+            BlendSpace::Srgb => [0.3, 0.59, 0.11],
+            BlendSpace::Linear => [0.2126, 0.7152, 0.0722],
+        }
+    }
+}
+
+fn separable(mode: BlendMode, cb: f64, cs: f64) -> f64 {
+    match mode {
+        BlendMode::Multiply => cb * cs,
+        BlendMode::Screen => cb + cs - cb * cs,
+        BlendMode::Overlay => separable(BlendMode::HardLight, cs, cb),
+        BlendMode::Darken => cb.min(cs),
+        BlendMode::Lighten => cb.max(cs),
+        BlendMode::ColorDodge => {
+            if cb == 0. {
+                0.
+            } else if cs >= 1. {
+                1.
+            } else {
+                (cb / (1. - cs)).min(1.)
+            }
+        },
+        BlendMode::ColorBurn => {
+            if cb >= 1. {
+                1.
+            } else if cs == 0. {
+                0.
+            } else {
+                1. - ((1. - cb) / cs).min(1.)
+            }
+        },
+        BlendMode::HardLight => {
+            if cs <= 0.5 {
+                cb * 2. * cs
+            } else {
+                separable(BlendMode::Screen, cb, 2. * cs - 1.)
+            }
+        },
+        BlendMode::SoftLight => {
+            if cs <= 0.5 {
+                cb - (1. - 2. * cs) * cb * (1. - cb)
+            } else {
+                let d = if cb <= 0.25 { ((16. * cb - 12.) * cb + 4.) * cb } else { cb.sqrt() };
+                cb + (2. * cs - 1.) * (d - cb)
+            }
+        },
+        BlendMode::Difference => (cb - cs).abs(),
+        BlendMode::Exclusion => cb + cs - 2. * cb * cs,
+        _ => cs,
+    }
+}
+
+fn lum(c: &[f64, ..3], w: &[f64, ..3]) -> f64 {
+    c[0] * w[0] + c[1] * w[1] + c[2] * w[2]
+}
+
+fn clip_color(c: [f64, ..3], w: &[f64, ..3]) -> [f64, ..3] {
+    let l = lum(&c, w);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    let mut c = c;
+    for v in c.iter_mut() {
+        if n < 0. {
+            *v = l + (*v - l) * l / (l - n);
+        }
+        if x > 1. {
+            *v = l + (*v - l) * (1. - l) / (x - l);
+        }
+    }
+    c
+}
+
+fn set_lum(c: &[f64, ..3], l: f64, w: &[f64, ..3]) -> [f64, ..3] {
+    let d = l - lum(c, w);
+    clip_color([c[0] + d, c[1] + d, c[2] + d], w)
+}
+
+fn sat(c: &[f64, ..3]) -> f64 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: &[f64, ..3], s: f64) -> [f64, ..3] {
+    let mut i = [0u, 1, 2];
+    i.sort_by(|&a, &b| c[a].partial_cmp(&c[b]).unwrap());
+    let (min, mid, max) = (i[0], i[1], i[2]);
+    let mut out = [0f64, ..3];
+    if c[max] > c[min] {
+        out[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+        out[max] = s;
+    }
+    out
+}
+
+/// The blended color B(Cb, Cs) of a backdrop and a source, before any alpha is applied.
+pub fn blend_color<U>(cb: &Color3<f64, U>, cs: &Color3<f64, U>, mode: BlendMode,
+                      space: BlendSpace) -> Color3<f64, U> {
+    let w = space.weights();
+    let (b, s) = (cb.to_array(), cs.to_array());
+    let c = match mode {
+        BlendMode::Hue => set_lum(&set_sat(&s, sat(&b)), lum(&b, &w), &w),
+        BlendMode::Saturation => set_lum(&set_sat(&b, sat(&s)), lum(&b, &w), &w),
+        BlendMode::Color => set_lum(&s, lum(&b, &w), &w),
+        BlendMode::Luminosity => set_lum(&b, lum(&s, &w), &w),
+        _ => [
+            separable(mode, b[0], s[0]),
+            separable(mode, b[1], s[1]),
+            separable(mode, b[2], s[2]),
+        ],
+    };
+    Color3::from_array(&c)
+}
+
+/// Blends a source over a backdrop, both given as encoded sRGB with straight alpha, and
+/// returns encoded sRGB with straight alpha.
+pub fn blend(src: &ColorRgbaF64<Encoded<Srgb>>, dst: &ColorRgbaF64<Encoded<Srgb>>,
+             mode: BlendMode, space: BlendSpace) -> ColorRgbaF64<Encoded<Srgb>> {
+    match space {
+        BlendSpace::Srgb => blend_in(src, dst, mode, space),
+        BlendSpace::Linear => blend_in(&src.decode(), &dst.decode(), mode, space).encode(),
+    }
+}
+
+fn blend_in<U>(src: &ColorRgbaF64<U>, dst: &ColorRgbaF64<U>, mode: BlendMode, space: BlendSpace)
+               -> ColorRgbaF64<U> {
+    let (&ColorAlpha(sc, sa), &ColorAlpha(dc, da)) = (src, dst);
+    let mixed = blend_color(&dc, &sc, mode, space);
+    let cs = sc * (1. - da) + mixed * da;
+    composite(&ColorAlpha(cs, sa).premultiply(), &dst.premultiply(), Operator::Over)
+        .unpremultiply()
+}
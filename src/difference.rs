@@ -0,0 +1,168 @@
+// Copyright © 2014, Peter Atashian
+
+//! Color difference formulas. The asymmetric ones treat the first color as the reference.
+
+use lab::ColorLab;
+use oklab::ColorOklab;
+
+/// The parametric weights of CIE94.
+#[deriving(Show, PartialEq)]
+pub struct Cie94 {
+    pub kl: f64,
+    pub kc: f64,
+    pub kh: f64,
+    pub k1: f64,
+    pub k2: f64,
+}
+
+pub static GRAPHIC_ARTS: Cie94 = Cie94 { kl: 1., kc: 1., kh: 1., k1: 0.045, k2: 0.015 };
+pub static TEXTILES: Cie94 = Cie94 { kl: 2., kc: 1., kh: 1., k1: 0.048, k2: 0.014 };
+
+/// The squared hue difference, which is what is left of ΔE76 after lightness and chroma.
+fn delta_h2(c1: &ColorLab<f64>, c2: &ColorLab<f64>, dc: f64) -> f64 {
+    let (da, db) = (c1.a - c2.a, c1.b - c2.b);
+    (da * da + db * db - dc * dc).max(0.)
+}
+
+/// CIE76, the Euclidean distance in Lab.
+pub fn delta_e76(c1: &ColorLab<f64>, c2: &ColorLab<f64>) -> f64 {
+    let (dl, da, db) = (c1.l - c2.l, c1.a - c2.a, c1.b - c2.b);
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+pub fn delta_e94(c1: &ColorLab<f64>, c2: &ColorLab<f64>, k: &Cie94) -> f64 {
+    let (l1, l2) = (c1.to_lch(), c2.to_lch());
+    let dl = l1.l - l2.l;
+    let dc = l1.c - l2.c;
+    let sc = 1. + k.k1 * l1.c;
+    let sh = 1. + k.k2 * l1.c;
+    let (x, y) = (dl / k.kl, dc / (k.kc * sc));
+    (x * x + y * y + delta_h2(c1, c2, dc) / (k.kh * sh * k.kh * sh)).sqrt()
+}
+
+/// CMC l:c, usually used as 2:1 for acceptability and 1:1 for perceptibility.
+pub fn delta_cmc(c1: &ColorLab<f64>, c2: &ColorLab<f64>, l: f64, c: f64) -> f64 {
+    let (l1, l2) = (c1.to_lch(), c2.to_lch());
+    let dl = l1.l - l2.l;
+    let dc = l1.c - l2.c;
+    let sl = if l1.l < 16. { 0.511 } else { 0.040975 * l1.l / (1. + 0.01765 * l1.l) };
+    let sc = 0.0638 * l1.c / (1. + 0.0131 * l1.c) + 0.638;
+    let c4 = l1.c.powi(4);
+    let f = (c4 / (c4 + 1900.)).sqrt();
+    let t = if l1.h >= 164. && l1.h <= 345. {
+        0.56 + (0.2 * (l1.h + 168.).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (l1.h + 35.).to_radians().cos()).abs()
+    };
+    let sh = sc * (f * t + 1. - f);
+    let (x, y) = (dl / (l * sl), dc / (c * sc));
+    (x * x + y * y + delta_h2(c1, c2, dc) / (sh * sh)).sqrt()
+}
+
+/// CIEDE2000 with the parametric weights kL, kC and kH, which are all 1 under reference
+/// conditions.
+pub fn ciede2000(c1: &ColorLab<f64>, c2: &ColorLab<f64>, kl: f64, kc: f64, kh: f64) -> f64 {
+    let cb = (c1.a.hypot(c1.b) + c2.a.hypot(c2.b)) * 0.5;
+    let cb7 = cb.powi(7);
+    let g = 0.5 * (1. - (cb7 / (cb7 + 25f64.powi(7))).sqrt());
+    let (c1p, h1p) = ::to_polar(c1.a * (1. + g), c1.b);
+    let (c2p, h2p) = ::to_polar(c2.a * (1. + g), c2.b);
+    let h1p = if c1p == 0. { 0. } else { h1p };
+    let h2p = if c2p == 0. { 0. } else { h2p };
+    let dl = c2.l - c1.l;
+    let dc = c2p - c1p;
+    let dh = if c1p * c2p == 0. {
+        0.
+    } else if h2p - h1p > 180. {
+        h2p - h1p - 360.
+    } else if h2p - h1p < -180. {
+        h2p - h1p + 360.
+    } else {
+        h2p - h1p
+    };
+    let dhh = 2. * (c1p * c2p).sqrt() * (dh * 0.5).to_radians().sin();
+    let lb = (c1.l + c2.l) * 0.5;
+    let cbp = (c1p + c2p) * 0.5;
+    let hb = if c1p * c2p == 0. {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180. {
+        (h1p + h2p) * 0.5
+    } else if h1p + h2p < 360. {
+        (h1p + h2p + 360.) * 0.5
+    } else {
+        (h1p + h2p - 360.) * 0.5
+    };
+    let t = 1. - 0.17 * (hb - 30.).to_radians().cos() + 0.24 * (2. * hb).to_radians().cos()
+        + 0.32 * (3. * hb + 6.).to_radians().cos() - 0.20 * (4. * hb - 63.).to_radians().cos();
+    let dtheta = 30. * (-((hb - 275.) / 25.).powi(2)).exp();
+    let cbp7 = cbp.powi(7);
+    let rc = 2. * (cbp7 / (cbp7 + 25f64.powi(7))).sqrt();
+    let lb50 = (lb - 50.) * (lb - 50.);
+    let sl = 1. + 0.015 * lb50 / (20. + lb50).sqrt();
+    let sc = 1. + 0.045 * cbp;
+    let sh = 1. + 0.015 * cbp * t;
+    let rt = -(2. * dtheta).to_radians().sin() * rc;
+    let (x, y, z) = (dl / (kl * sl), dc / (kc * sc), dhh / (kh * sh));
+    (x * x + y * y + z * z + rt * y * z).sqrt()
+}
+
+/// The Euclidean distance in Oklab, where a just noticeable difference is roughly 0.02.
+pub fn delta_eok(c1: &ColorOklab<f64>, c2: &ColorOklab<f64>) -> f64 {
+    let (dl, da, db) = (c1.l - c2.l, c1.a - c2.a, c1.b - c2.b);
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use lab::ColorLab;
+
+    /// Sharma, Wu and Dalal's test data as L, a, b of each pair followed by ΔE00.
+    static SHARMA: [[f64, ..7], ..34] = [
+        [50.0000, 2.6772, -79.7751, 50.0000, 0.0000, -82.7485, 2.0425],
+        [50.0000, 3.1571, -77.2803, 50.0000, 0.0000, -82.7485, 2.8615],
+        [50.0000, 2.8361, -74.0200, 50.0000, 0.0000, -82.7485, 3.4412],
+        [50.0000, -1.3802, -84.2814, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, -1.1848, -84.8006, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, -0.9009, -85.5211, 50.0000, 0.0000, -82.7485, 1.0000],
+        [50.0000, 0.0000, 0.0000, 50.0000, -1.0000, 2.0000, 2.3669],
+        [50.0000, -1.0000, 2.0000, 50.0000, 0.0000, 0.0000, 2.3669],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0009, 7.1792],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0010, 7.1792],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0011, 7.2195],
+        [50.0000, 2.4900, -0.0010, 50.0000, -2.4900, 0.0012, 7.2195],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0009, -2.4900, 4.8045],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0010, -2.4900, 4.8045],
+        [50.0000, -0.0010, 2.4900, 50.0000, 0.0011, -2.4900, 4.7461],
+        [50.0000, 2.5000, 0.0000, 50.0000, 0.0000, -2.5000, 4.3065],
+        [50.0000, 2.5000, 0.0000, 73.0000, 25.0000, -18.0000, 27.1492],
+        [50.0000, 2.5000, 0.0000, 61.0000, -5.0000, 29.0000, 22.8977],
+        [50.0000, 2.5000, 0.0000, 56.0000, -27.0000, -3.0000, 31.9030],
+        [50.0000, 2.5000, 0.0000, 58.0000, 24.0000, 15.0000, 19.4535],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.1736, 0.5854, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.2972, 0.0000, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 1.8634, 0.5757, 1.0000],
+        [50.0000, 2.5000, 0.0000, 50.0000, 3.2592, 0.3350, 1.0000],
+        [60.2574, -34.0099, 36.2677, 60.4626, -34.1751, 39.4387, 1.2644],
+        [63.0109, -31.0961, -5.8663, 62.8187, -29.7946, -4.0864, 1.2630],
+        [61.2901, 3.7196, -5.3901, 61.4292, 2.2480, -4.9620, 1.8731],
+        [35.0831, -44.1164, 3.7933, 35.0232, -40.0716, 1.5901, 1.8645],
+        [22.7233, 20.0904, -46.6940, 23.0331, 14.9730, -42.5619, 2.0373],
+        [36.4612, 47.8580, 18.3852, 36.2715, 50.5065, 21.2231, 1.4146],
+        [90.8027, -2.0831, 1.4410, 91.1528, -1.6435, 0.0447, 1.4441],
+        [90.9257, -0.5406, -0.9208, 88.6381, -0.8985, -0.7239, 1.5381],
+        [6.7747, -0.2908, -2.4247, 5.8714, -0.0985, -2.2286, 0.6377],
+        [2.0776, 0.0795, -1.1350, 0.9033, -0.0636, -0.5514, 0.9082],
+    ];
+
+    #[test]
+    fn ciede2000_sharma() {
+        for p in SHARMA.iter() {
+            let c1 = ColorLab { l: p[0], a: p[1], b: p[2] };
+            let c2 = ColorLab { l: p[3], a: p[4], b: p[5] };
+            let (d1, d2) = (super::ciede2000(&c1, &c2, 1., 1., 1.),
+                            super::ciede2000(&c2, &c1, 1., 1., 1.));
+            assert!((d1 - p[6]).abs() < 1e-4, "{} {}", p[6], d1);
+            assert!((d2 - p[6]).abs() < 1e-4, "{} {}", p[6], d2);
+        }
+    }
+}
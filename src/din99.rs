@@ -0,0 +1,51 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use lab::ColorLab;
+use {ColorXyy, ColorXyz};
+
+/// DIN99 of DIN 6176, a logarithmic compression of CIELAB.
+#[deriving(Show)]
+pub struct ColorDin99<T> {
+    pub l: T,
+    pub a: T,
+    pub b: T,
+}
+
+components!(ColorDin99 { l, a, b })
+
+impl ColorDin99<f64> {
+    /// Compresses CIELAB with the lightness weight `ke` and chroma weight `kch`, which are both 1
+    /// under the reference conditions.
+    pub fn from_lab(c: &ColorLab<f64>, ke: f64, kch: f64) -> ColorDin99<f64> {
+        let (hs, hc) = 16f64.to_radians().sin_cos();
+        let e = c.a * hc + c.b * hs;
+        let f = 0.7 * (c.b * hc - c.a * hs);
+        let g = e.hypot(f);
+        let c99 = (1. + 0.045 * g).ln() / (0.045 * kch * ke);
+        let h = f.atan2(e);
+        ColorDin99 {
+            l: 105.51 / ke * (1. + 0.0158 * c.l).ln(),
+            a: c99 * h.cos(),
+            b: c99 * h.sin(),
+        }
+    }
+    pub fn to_lab(&self, ke: f64, kch: f64) -> ColorLab<f64> {
+        let (hs, hc) = 16f64.to_radians().sin_cos();
+        let c99 = self.a.hypot(self.b);
+        let g = ((0.045 * c99 * kch * ke).exp() - 1.) / 0.045;
+        let h = self.b.atan2(self.a);
+        let (e, f) = (g * h.cos(), g * h.sin() / 0.7);
+        ColorLab {
+            l: ((self.l * ke / 105.51).exp() - 1.) / 0.0158,
+            a: e * hc - f * hs,
+            b: e * hs + f * hc,
+        }
+    }
+    pub fn from_xyz(c: &ColorXyz, white: &ColorXyy<f64>) -> ColorDin99<f64> {
+        ColorDin99::from_lab(&ColorLab::from_xyz(c, white), 1., 1.)
+    }
+    pub fn to_xyz(&self, white: &ColorXyy<f64>) -> ColorXyz {
+        self.to_lab(1., 1.).to_xyz(white)
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright © 2014, Peter Atashian
+
+use std::cmp;
+use ycbcr::ColorYcbcrInt;
+
+#[deriving(Show, Clone, PartialEq)]
+pub enum ChromaFormat {
+    Yuv444,
+    Yuv422,
+    Yuv420,
+}
+
+impl ChromaFormat {
+    /// How many luma samples share a chroma sample horizontally and vertically.
+    pub fn factors(&self) -> (uint, uint) {
+        match *self {
+            ChromaFormat::Yuv444 => (1, 1),
+            ChromaFormat::Yuv422 => (2, 1),
+            ChromaFormat::Yuv420 => (2, 2),
+        }
+    }
+    pub fn chroma_size(&self, width: uint, height: uint) -> (uint, uint) {
+        let (fh, fv) = self.factors();
+        ((width + fh - 1) / fh, (height + fv - 1) / fv)
+    }
+}
+
+/// Where chroma samples sit relative to the luma samples they cover.
+#[deriving(Show, Clone, PartialEq)]
+pub enum ChromaSiting {
+    /// Centered between luma samples in both directions, as in JPEG and MPEG-1.
+    Center,
+    /// Co-sited with the left luma sample and centered vertically, as in MPEG-2 and H.264.
+    Left,
+    /// Co-sited with the top left luma sample, as in BT.2020 and DV.
+    TopLeft,
+}
+
+impl ChromaSiting {
+    /// The position of the first chroma sample in luma samples for the given factors.
+    fn offsets(&self, fh: uint, fv: uint) -> (f64, f64) {
+        let center = |f: uint| (f as f64 - 1.) * 0.5;
+        match *self {
+            ChromaSiting::Center => (center(fh), center(fv)),
+            ChromaSiting::Left => (0., center(fv)),
+            ChromaSiting::TopLeft => (0., 0.),
+        }
+    }
+}
+
+#[deriving(Show, Clone, PartialEq)]
+pub enum ChromaFilter {
+    /// Takes the closest sample.
+    Nearest,
+    /// A triangle filter spanning one chroma sample on either side, which is bilinear
+    /// interpolation when upsampling.
+    Linear,
+}
+
+/// Resamples a line of luma spaced samples to chroma spacing when `down` is set, and chroma
+/// spaced samples back to `len` luma spaced samples otherwise.
+fn resample(src: &[f64], len: uint, factor: uint, offset: f64, down: bool, filter: ChromaFilter)
+            -> Vec<f64> {
+    fn clamp(src: &[f64], i: int) -> f64 {
+        src[cmp::max(cmp::min(i, src.len() as int - 1), 0) as uint]
+    }
+    let f = factor as f64;
+    range(0, len).map(|i| {
+        if down {
+            let p = i as f64 * f + offset;
+            match filter {
+                ChromaFilter::Nearest => clamp(src, (p + 0.5).floor() as int),
+                ChromaFilter::Linear => {
+                    let lo = (p - f).floor() as int;
+                    let hi = (p + f).ceil() as int;
+                    let (mut sum, mut total) = (0., 0.);
+                    for x in range(lo, hi + 1) {
+                        let w = 1. - (x as f64 - p).abs() / f;
+                        if w > 0. {
+                            sum += w * clamp(src, x);
+                            total += w;
+                        }
+                    }
+                    sum / total
+                },
+            }
+        } else {
+            let c = (i as f64 - offset) / f;
+            match filter {
+                ChromaFilter::Nearest => clamp(src, (c + 0.5).floor() as int),
+                ChromaFilter::Linear => {
+                    let lo = c.floor();
+                    let t = c - lo;
+                    clamp(src, lo as int) * (1. - t) + clamp(src, lo as int + 1) * t
+                },
+            }
+        }
+    }).collect()
+}
+
+#[deriving(Show, Clone, PartialEq)]
+pub struct Plane {
+    pub width: uint,
+    pub height: uint,
+    pub data: Vec<u16>,
+}
+
+impl Plane {
+    pub fn new(width: uint, height: uint) -> Plane {
+        Plane {
+            width: width,
+            height: height,
+            data: Vec::from_elem(width * height, 0),
+        }
+    }
+    pub fn get(&self, x: uint, y: uint) -> u16 {
+        self.data[y * self.width + x]
+    }
+    pub fn set(&mut self, x: uint, y: uint, v: u16) {
+        self.data[y * self.width + x] = v;
+    }
+    /// Resamples both directions separably, rounding back to code values at the end.
+    fn resample(&self, width: uint, height: uint, (fh, fv): (uint, uint), (oh, ov): (f64, f64),
+                down: bool, filter: ChromaFilter, max: u16) -> Plane {
+        let data: Vec<f64> = self.data.iter().map(|&v| v as f64).collect();
+        let mut rows = Vec::with_capacity(width * self.height);
+        for y in range(0, self.height) {
+            let line = data.slice(y * self.width, (y + 1) * self.width);
+            rows.extend(resample(line, width, fh, oh, down, filter).into_iter());
+        }
+        let mut plane = Plane::new(width, height);
+        for x in range(0, width) {
+            let column: Vec<f64> = range(0, self.height).map(|y| rows[y * width + x]).collect();
+            let column = resample(column.as_slice(), height, fv, ov, down, filter);
+            for (y, &v) in column.iter().enumerate() {
+                plane.set(x, y, v.round().min(max as f64).max(0.) as u16);
+            }
+        }
+        plane
+    }
+}
+
+/// A YCbCr frame stored as three planes of code values.
+#[deriving(Show, Clone, PartialEq)]
+pub struct Frame {
+    pub width: uint,
+    pub height: uint,
+    pub format: ChromaFormat,
+    pub bit_depth: uint,
+    pub y: Plane,
+    pub cb: Plane,
+    pub cr: Plane,
+}
+
+impl Frame {
+    pub fn new(width: uint, height: uint, format: ChromaFormat, bit_depth: uint) -> Frame {
+        let (cw, ch) = format.chroma_size(width, height);
+        Frame {
+            width: width,
+            height: height,
+            format: format,
+            bit_depth: bit_depth,
+            y: Plane::new(width, height),
+            cb: Plane::new(cw, ch),
+            cr: Plane::new(cw, ch),
+        }
+    }
+    /// Builds a 4:4:4 frame from pixels in row-major order.
+    pub fn from_pixels(width: uint, height: uint, bit_depth: uint, pixels: &[ColorYcbcrInt])
+                       -> Frame {
+        assert_eq!(pixels.len(), width * height);
+        let mut frame = Frame::new(width, height, ChromaFormat::Yuv444, bit_depth);
+        frame.y.data = pixels.iter().map(|p| p.y).collect();
+        frame.cb.data = pixels.iter().map(|p| p.cb).collect();
+        frame.cr.data = pixels.iter().map(|p| p.cr).collect();
+        frame
+    }
+    /// The pixel at the given luma position, using the chroma sample that covers it.
+    pub fn pixel(&self, x: uint, y: uint) -> ColorYcbcrInt {
+        let (fh, fv) = self.format.factors();
+        ColorYcbcrInt {
+            y: self.y.get(x, y),
+            cb: self.cb.get(x / fh, y / fv),
+            cr: self.cr.get(x / fh, y / fv),
+        }
+    }
+    /// Converts to another chroma format, going through 4:4:4 when neither side is 4:4:4.
+    pub fn to_format(&self, format: ChromaFormat, siting: ChromaSiting, filter: ChromaFilter)
+                     -> Frame {
+        if format == self.format {
+            return self.clone()
+        }
+        // Planes hold `u16` code values, so anything deeper saturates at their range.
+        let max = ((1u << cmp::min(self.bit_depth, 16)) - 1) as u16;
+        let full = if self.format == ChromaFormat::Yuv444 {
+            self.clone()
+        } else {
+            let (fh, fv) = self.format.factors();
+            let offsets = siting.offsets(fh, fv);
+            let up = |p: &Plane| {
+                p.resample(self.width, self.height, (fh, fv), offsets, false, filter, max)
+            };
+            Frame {
+                width: self.width,
+                height: self.height,
+                format: ChromaFormat::Yuv444,
+                bit_depth: self.bit_depth,
+                y: self.y.clone(),
+                cb: up(&self.cb),
+                cr: up(&self.cr),
+            }
+        };
+        if format == ChromaFormat::Yuv444 {
+            return full
+        }
+        let (fh, fv) = format.factors();
+        let offsets = siting.offsets(fh, fv);
+        let (cw, ch) = format.chroma_size(self.width, self.height);
+        let down = |p: &Plane| p.resample(cw, ch, (fh, fv), offsets, true, filter, max);
+        Frame {
+            width: self.width,
+            height: self.height,
+            format: format,
+            bit_depth: self.bit_depth,
+            y: full.y.clone(),
+            cb: down(&full.cb),
+            cr: down(&full.cr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{resample, ChromaFilter, ChromaFormat, ChromaSiting, Frame};
+
+    #[test]
+    fn constant_round_trip() {
+        let sitings = [ChromaSiting::Center, ChromaSiting::Left, ChromaSiting::TopLeft];
+        let filters = [ChromaFilter::Nearest, ChromaFilter::Linear];
+        let formats = [ChromaFormat::Yuv422, ChromaFormat::Yuv420];
+        let mut frame = Frame::new(5, 3, ChromaFormat::Yuv444, 10);
+        for (i, v) in frame.y.data.iter_mut().enumerate() {
+            *v = (i * 60) as u16;
+        }
+        frame.cb.data = Vec::from_elem(15, 300);
+        frame.cr.data = Vec::from_elem(15, 1023);
+        for &siting in sitings.iter() {
+            for &filter in filters.iter() {
+                for &format in formats.iter() {
+                    let sub = frame.to_format(format, siting, filter);
+                    assert_eq!(sub.to_format(ChromaFormat::Yuv444, siting, filter), frame);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn linear_weights() {
+        let ramp: Vec<f64> = range(0u, 16).map(|i| i as f64).collect();
+        for &siting in [ChromaSiting::Center, ChromaSiting::Left].iter() {
+            let (offset, _) = siting.offsets(2, 1);
+            // Only the ends clamp, so every interior sample of a ramp lands on the ramp.
+            let down = resample(ramp.as_slice(), 8, 2, offset, true, ChromaFilter::Linear);
+            for i in range(1u, 7) {
+                assert!((down[i] - (i as f64 * 2. + offset)).abs() < 1e-12, "{} {}", i, down);
+            }
+            let chroma: Vec<f64> = range(0u, 8).map(|i| i as f64 * 2. + offset).collect();
+            let up = resample(chroma.as_slice(), 16, 2, offset, false, ChromaFilter::Linear);
+            for i in range(1u, 15) {
+                assert!((up[i] - i as f64).abs() < 1e-12, "{} {}", i, up);
+            }
+        }
+    }
+}
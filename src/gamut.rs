@@ -0,0 +1,182 @@
+// Copyright © 2014, Peter Atashian
+
+//! Gamut detection and mapping. Colors are given as XYZ relative to the white of the target
+//! space `S` and mapped to linear RGB in that space.
+
+use adaptation::{AdaptationMethod, ChromaticAdaptation};
+use difference;
+use lab::ColorLch;
+use oklab::{ColorOklab, ColorOklch};
+use unit::{mod, Linear, Space};
+use {Color3, ColorSpaceMatrix, ColorXyz, D65};
+
+/// The just noticeable difference in Oklab used by CSS Color 4.
+pub static CSS_JND: f64 = 0.02;
+/// The linear value of the usual 18% mid-gray anchor.
+pub static MID_GRAY: f64 = 0.18;
+
+/// How far outside [0, 1] a channel may be and still count as in gamut, to absorb rounding.
+static TOLERANCE: f64 = 1e-7;
+/// How precisely the chroma searches converge.
+static CHROMA_EPSILON: f64 = 1e-4;
+
+#[deriving(Show, PartialEq)]
+pub enum GamutMapping {
+    /// Clamps each channel, which can shift hue and lightness.
+    Clip,
+    /// Reduces CIE LCh chroma at constant lightness and hue.
+    LchChroma,
+    /// Reduces Oklch chroma at constant lightness and hue.
+    OklchChroma,
+    /// The CSS Color 4 algorithm, which reduces Oklch chroma until clipping is within the given
+    /// ΔEOK, usually `CSS_JND`.
+    Css4(f64),
+    /// Moves toward a gray of the given linear value, such as `MID_GRAY`, until inside.
+    MidGray(f64),
+}
+
+fn inside<S>(c: &Color3<f64, Linear<S>>) -> bool {
+    c.to_array().iter().all(|&v| v >= -TOLERANCE && v <= 1. + TOLERANCE)
+}
+
+fn clip<S>(c: &Color3<f64, Linear<S>>) -> Color3<f64, Linear<S>> {
+    let &Color3(r, g, b) = c;
+    Color3(r.max(0.).min(1.), g.max(0.).min(1.), b.max(0.).min(1.))
+}
+
+pub fn in_gamut<S>(c: &ColorXyz) -> bool where S: Space {
+    inside(&c.to_linear::<S>())
+}
+
+/// Oklch is defined on D65, so other whites are adapted to it and back.
+struct Oklch<S> {
+    matrix: ColorSpaceMatrix<S>,
+    to_d65: ChromaticAdaptation,
+    from_d65: ChromaticAdaptation,
+}
+
+impl<S> Oklch<S> where S: Space {
+    fn new() -> Oklch<S> {
+        let w = &unit::space_of::<S>().w;
+        let to_d65 = ChromaticAdaptation::from_whites(AdaptationMethod::Bradford, w, &D65);
+        Oklch {
+            matrix: ColorSpaceMatrix::new(),
+            from_d65: to_d65.inverse(),
+            to_d65: to_d65,
+        }
+    }
+    fn from_xyz(&self, c: &ColorXyz) -> ColorOklch<f64> {
+        ColorOklch::from_xyz(&self.to_d65.apply(c))
+    }
+    fn to_rgb(&self, c: &ColorOklch<f64>) -> Color3<f64, Linear<S>> {
+        self.matrix.to_rgb(&self.from_d65.apply(&c.to_xyz()))
+    }
+    fn from_rgb(&self, c: &Color3<f64, Linear<S>>) -> ColorOklab<f64> {
+        ColorOklab::from_xyz(&self.to_d65.apply(&self.matrix.to_xyz(c)))
+    }
+}
+
+/// Halves the chroma range until the largest in gamut chroma is found.
+fn reduce_chroma<S>(c: f64, rgb: |f64| -> Color3<f64, Linear<S>>) -> Color3<f64, Linear<S>> {
+    let (mut lo, mut hi) = (0., c);
+    while hi - lo > CHROMA_EPSILON {
+        let mid = (lo + hi) * 0.5;
+        if inside(&rgb(mid)) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    clip(&rgb(lo))
+}
+
+fn css4<S>(c: &ColorXyz, jnd: f64) -> Color3<f64, Linear<S>> where S: Space {
+    let ok: Oklch<S> = Oklch::new();
+    let origin = ok.from_xyz(c);
+    if origin.l >= 1. {
+        return Color3::white()
+    } else if origin.l <= 0. {
+        return Color3(0., 0., 0.)
+    }
+    let with_chroma = |chroma: f64| ColorOklch { l: origin.l, c: chroma, h: origin.h };
+    let error = |current: &ColorOklch<f64>, clipped: &Color3<f64, Linear<S>>| {
+        difference::delta_eok(&current.to_oklab(), &ok.from_rgb(clipped))
+    };
+    let mut clipped = clip(&ok.to_rgb(&origin));
+    if error(&origin, &clipped) < jnd {
+        return clipped
+    }
+    let (mut lo, mut hi) = (0., origin.c);
+    let mut lo_inside = true;
+    while hi - lo > CHROMA_EPSILON {
+        let chroma = (lo + hi) * 0.5;
+        let current = with_chroma(chroma);
+        let rgb = ok.to_rgb(&current);
+        if lo_inside && inside(&rgb) {
+            lo = chroma;
+            continue
+        }
+        clipped = clip(&rgb);
+        let e = error(&current, &clipped);
+        if e < jnd {
+            if jnd - e < CHROMA_EPSILON {
+                return clipped
+            }
+            lo_inside = false;
+            lo = chroma;
+        } else {
+            hi = chroma;
+        }
+    }
+    clipped
+}
+
+/// Moves along the line toward the anchor gray, stopping where it first leaves the cube.
+fn toward_gray<S>(c: &Color3<f64, Linear<S>>, anchor: f64) -> Color3<f64, Linear<S>> {
+    let t = c.to_array().iter().fold(1f64, |t, &v| {
+        if v > 1. {
+            t.min((1. - anchor) / (v - anchor))
+        } else if v < 0. {
+            t.min(anchor / (anchor - v))
+        } else {
+            t
+        }
+    });
+    clip(&(*c * t + Color3(anchor, anchor, anchor) * (1. - t)))
+}
+
+/// Maps a color into the gamut of the color space `S`, returning linear RGB in that space.
+pub fn map<S>(c: &ColorXyz, method: GamutMapping) -> Color3<f64, Linear<S>> where S: Space {
+    let rgb = c.to_linear::<S>();
+    if inside(&rgb) {
+        return clip(&rgb)
+    }
+    match method {
+        GamutMapping::Clip => clip(&rgb),
+        GamutMapping::LchChroma => {
+            let w = &unit::space_of::<S>().w;
+            let lch = ColorLch::from_xyz(c, w);
+            if lch.l >= 100. {
+                return Color3::white()
+            } else if lch.l <= 0. {
+                return Color3(0., 0., 0.)
+            }
+            let m: ColorSpaceMatrix<S> = ColorSpaceMatrix::new();
+            reduce_chroma(lch.c, |chroma| {
+                m.to_rgb(&ColorLch { l: lch.l, c: chroma, h: lch.h }.to_xyz(w))
+            })
+        },
+        GamutMapping::OklchChroma => {
+            let ok: Oklch<S> = Oklch::new();
+            let lch = ok.from_xyz(c);
+            if lch.l >= 1. {
+                return Color3::white()
+            } else if lch.l <= 0. {
+                return Color3(0., 0., 0.)
+            }
+            reduce_chroma(lch.c, |chroma| ok.to_rgb(&ColorOklch { l: lch.l, c: chroma, h: lch.h }))
+        },
+        GamutMapping::Css4(jnd) => css4(c, jnd),
+        GamutMapping::MidGray(anchor) => toward_gray(&rgb, anchor),
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright © 2014, Peter Atashian
+
+//! Hue based models over RGB in any unit. They are most often used on encoded sRGB.
+
+use component::Component;
+use unit::Rgb;
+use Color3;
+
+/// Maps a hue in degrees of any value onto the [0, 6) range used by `Color3::from_hue`.
+/// Hues that are not finite are treated as red.
+fn sector(hue: f64) -> f64 {
+    if !hue.is_finite() {
+        return 0.
+    }
+    let h = (hue % 360. + 360.) % 360. / 60.;
+    if h >= 6. { 0. } else { h }
+}
+
+/// The hue in degrees along with the largest and smallest channel. Grays have a hue of 0.
+fn hue_range<U>(c: &Color3<f64, U>) -> (f64, f64, f64) {
+    let &Color3(r, g, b) = c;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+    let h = if d == 0. {
+        0.
+    } else if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.
+    } else {
+        (r - g) / d + 4.
+    };
+    let h = h * 60.;
+    (if h < 0. { h + 360. } else { h }, max, min)
+}
+
+fn from_chroma<U>(hue: f64, chroma: f64, min: f64) -> Color3<f64, U> where U: Rgb {
+    Color3::from_hue(sector(hue)) * chroma + Color3::white() * min
+}
+
+/// Hue in degrees, with saturation and lightness in [0, 1].
+#[deriving(Show)]
+pub struct ColorHsl<T> {
+    pub h: T,
+    pub s: T,
+    pub l: T,
+}
+
+components!(ColorHsl { h, s, l })
+
+impl ColorHsl<f64> {
+    pub fn from_rgb<U>(c: &Color3<f64, U>) -> ColorHsl<f64> where U: Rgb {
+        let (h, max, min) = hue_range(c);
+        let l = (max + min) / 2.;
+        let d = 1. - (2. * l - 1.).abs();
+        ColorHsl {
+            h: h,
+            s: if d == 0. { 0. } else { (max - min) / d },
+            l: l,
+        }
+    }
+    pub fn to_rgb<U>(&self) -> Color3<f64, U> where U: Rgb {
+        let c = (1. - (2. * self.l - 1.).abs()) * self.s;
+        from_chroma(self.h, c, self.l - c / 2.)
+    }
+}
+
+/// Hue in degrees, with saturation and value in [0, 1].
+#[deriving(Show)]
+pub struct ColorHsv<T> {
+    pub h: T,
+    pub s: T,
+    pub v: T,
+}
+
+components!(ColorHsv { h, s, v })
+
+impl ColorHsv<f64> {
+    pub fn from_rgb<U>(c: &Color3<f64, U>) -> ColorHsv<f64> where U: Rgb {
+        let (h, max, min) = hue_range(c);
+        ColorHsv {
+            h: h,
+            s: if max == 0. { 0. } else { (max - min) / max },
+            v: max,
+        }
+    }
+    pub fn to_rgb<U>(&self) -> Color3<f64, U> where U: Rgb {
+        let c = self.v * self.s;
+        from_chroma(self.h, c, self.v - c)
+    }
+}
+
+/// Hue in degrees, with whiteness and blackness in [0, 1].
+#[deriving(Show)]
+pub struct ColorHwb<T> {
+    pub h: T,
+    pub w: T,
+    pub b: T,
+}
+
+components!(ColorHwb { h, w, b })
+
+impl ColorHwb<f64> {
+    pub fn from_rgb<U>(c: &Color3<f64, U>) -> ColorHwb<f64> where U: Rgb {
+        let (h, max, min) = hue_range(c);
+        ColorHwb {
+            h: h,
+            w: min,
+            b: 1. - max,
+        }
+    }
+    pub fn to_rgb<U>(&self) -> Color3<f64, U> where U: Rgb {
+        let sum = self.w + self.b;
+        if sum >= 1. {
+            return Color3::white() * (self.w / sum)
+        }
+        from_chroma(self.h, 1. - sum, self.w)
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use {Color3, ColorXyy, ColorXyz};
+
+/// Hunter L,a,b relative to a reference white.
+#[deriving(Show)]
+pub struct ColorHunterLab<T> {
+    pub l: T,
+    pub a: T,
+    pub b: T,
+}
+
+components!(ColorHunterLab { l, a, b })
+
+/// The chromaticity coefficients Ka and Kb for a white, which Hunter defined on a scale where the
+/// white has a luminance of 100.
+fn coefficients(w: &ColorXyz) -> (f64, f64) {
+    let &Color3(x, y, z) = w;
+    (175. / 198.04 * 100. * (x + y) / y, 70. / 218.11 * 100. * (y + z) / y)
+}
+
+impl ColorHunterLab<f64> {
+    pub fn from_xyz(c: &ColorXyz, white: &ColorXyy<f64>) -> ColorHunterLab<f64> {
+        let w = white.to_xyz();
+        let (ka, kb) = coefficients(&w);
+        let (&Color3(x, y, z), Color3(wx, wy, wz)) = (c, w);
+        let (x, y, z) = (x / wx, y / wy, z / wz);
+        let sy = y.sqrt();
+        if sy == 0. {
+            return ColorHunterLab { l: 0., a: 0., b: 0. }
+        }
+        ColorHunterLab {
+            l: 100. * sy,
+            a: ka * (x - y) / sy,
+            b: kb * (y - z) / sy,
+        }
+    }
+    pub fn to_xyz(&self, white: &ColorXyy<f64>) -> ColorXyz {
+        let w = white.to_xyz();
+        let (ka, kb) = coefficients(&w);
+        let sy = self.l / 100.;
+        let y = sy * sy;
+        let Color3(wx, wy, wz) = w;
+        Color3(wx * (self.a / ka * sy + y), wy * y, wz * (y - self.b / kb * sy))
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use matrix::{mod, Matrix3};
+use transfer::TransferFunction;
+use unit::{Linear, Rec2020};
+use Color3;
+
+pub static REC2020_TO_LMS: Matrix3 = [
+    [1688. / 4096., 2146. / 4096., 262. / 4096.],
+    [683. / 4096., 2951. / 4096., 462. / 4096.],
+    [99. / 4096., 309. / 4096., 3688. / 4096.],
+];
+
+pub static LMS_TO_REC2020: Matrix3 = [
+    [3.4366066943, -2.5064521187, 0.0698454243],
+    [-0.7913295556, 1.9836004518, -0.1922708962],
+    [-0.0259498997, -0.0989137147, 1.1248636144],
+];
+
+pub static LMS_TO_ICTCP_PQ: Matrix3 = [
+    [2048. / 4096., 2048. / 4096., 0.],
+    [6610. / 4096., -13613. / 4096., 7003. / 4096.],
+    [17933. / 4096., -17390. / 4096., -543. / 4096.],
+];
+
+pub static ICTCP_TO_LMS_PQ: Matrix3 = [
+    [1.0000000000, 0.0086090370, 0.1110296250],
+    [1.0000000000, -0.0086090370, -0.1110296250],
+    [1.0000000000, 0.5600313357, -0.3206271750],
+];
+
+pub static LMS_TO_ICTCP_HLG: Matrix3 = [
+    [2048. / 4096., 2048. / 4096., 0.],
+    [3625. / 4096., -7465. / 4096., 3840. / 4096.],
+    [9500. / 4096., -9212. / 4096., -288. / 4096.],
+];
+
+pub static ICTCP_TO_LMS_HLG: Matrix3 = [
+    [1.0000000000, 0.0157185801, 0.2095810681],
+    [1.0000000000, -0.0157185801, -0.2095810681],
+    [1.0000000000, 1.0212710798, -0.6052744910],
+];
+
+/// The nominal peak luminance of an HLG display in cd/m², for which the system gamma is 1.2.
+pub static HLG_PEAK: f64 = 1000.;
+static HLG_GAMMA: f64 = 1.2;
+
+/// The ITU-R BT.2100 signal used to encode the cone responses.
+#[deriving(Show, PartialEq)]
+pub enum HdrSignal {
+    Pq,
+    Hlg,
+}
+
+fn rec2020_luminance(c: &[f64, ..3]) -> f64 {
+    0.2627 * c[0] + 0.6780 * c[1] + 0.0593 * c[2]
+}
+
+/// ITU-R BT.2100 ICtCp.
+#[deriving(Show)]
+pub struct ColorIctcp<T> {
+    pub i: T,
+    pub ct: T,
+    pub cp: T,
+}
+
+components!(ColorIctcp { i, ct, cp })
+
+impl ColorIctcp<f64> {
+    /// Converts linear Rec.2020 display light in which 1 is a reference white of
+    /// `reference_white` cd/m², such as the 203 cd/m² of ITU-R BT.2408.
+    pub fn from_rec2020(c: &Color3<f64, Linear<Rec2020>>, signal: HdrSignal, reference_white: f64)
+                        -> ColorIctcp<f64> {
+        let (lms, tf, m) = match signal {
+            HdrSignal::Pq => {
+                let rgb = (*c * (reference_white / 10000.)).to_array();
+                (matrix::transform(&REC2020_TO_LMS, &rgb), TransferFunction::Pq, LMS_TO_ICTCP_PQ)
+            },
+            HdrSignal::Hlg => {
+                // HLG encodes scene light, so undo the OOTF of the nominal display first.
+                let rgb = (*c * (reference_white / HLG_PEAK)).to_array();
+                let y = rec2020_luminance(&rgb);
+                let k = if y > 0. { y.powf((1. - HLG_GAMMA) / HLG_GAMMA) } else { 0. };
+                let rgb = [rgb[0] * k, rgb[1] * k, rgb[2] * k];
+                (matrix::transform(&REC2020_TO_LMS, &rgb), TransferFunction::Hlg, LMS_TO_ICTCP_HLG)
+            },
+        };
+        let lms = [tf.encode(lms[0]), tf.encode(lms[1]), tf.encode(lms[2])];
+        let itp = matrix::transform(&m, &lms);
+        ColorIctcp {
+            i: itp[0],
+            ct: itp[1],
+            cp: itp[2],
+        }
+    }
+    pub fn to_rec2020(&self, signal: HdrSignal, reference_white: f64)
+                      -> Color3<f64, Linear<Rec2020>> {
+        let itp = [self.i, self.ct, self.cp];
+        let (tf, m) = match signal {
+            HdrSignal::Pq => (TransferFunction::Pq, ICTCP_TO_LMS_PQ),
+            HdrSignal::Hlg => (TransferFunction::Hlg, ICTCP_TO_LMS_HLG),
+        };
+        let lms = matrix::transform(&m, &itp);
+        let lms = [tf.decode(lms[0]), tf.decode(lms[1]), tf.decode(lms[2])];
+        let rgb = matrix::transform(&LMS_TO_REC2020, &lms);
+        let c: Color3<f64, Linear<Rec2020>> = Color3::from_array(&rgb);
+        match signal {
+            HdrSignal::Pq => c * (10000. / reference_white),
+            HdrSignal::Hlg => {
+                let y = rec2020_luminance(&rgb);
+                let k = if y > 0. { y.powf(HLG_GAMMA - 1.) } else { 0. };
+                c * (k * HLG_PEAK / reference_white)
+            },
+        }
+    }
+    /// The ΔE_ITP color difference of ITU-R BT.2124, meant for PQ encoded ICtCp. A difference of
+    /// 1 is about one just noticeable difference.
+    pub fn delta_e_itp(&self, o: &ColorIctcp<f64>) -> f64 {
+        let di = self.i - o.i;
+        let dt = 0.5 * (self.ct - o.ct);
+        let dp = self.cp - o.cp;
+        720. * (di * di + dt * dt + dp * dp).sqrt()
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use matrix::{mod, Matrix3};
+use {Color3, ColorXyz};
+
+pub static XYZ_TO_LMS: Matrix3 = [
+    [0.4002, 0.7075, -0.0807],
+    [-0.2280, 1.1500, 0.0612],
+    [0.0000, 0.0000, 0.9184],
+];
+
+pub static LMS_TO_XYZ: Matrix3 = [
+    [1.8502429449, -1.1383016379, 0.2384349585],
+    [0.3668307752, 0.6438845448, -0.0106734436],
+    [0.0000000000, 0.0000000000, 1.0888501742],
+];
+
+pub static LMS_TO_IPT: Matrix3 = [
+    [0.4000, 0.4000, 0.2000],
+    [4.4550, -4.8510, 0.3960],
+    [0.8056, 0.3572, -1.1628],
+];
+
+pub static IPT_TO_LMS: Matrix3 = [
+    [1.0000000000, 0.0975689305, 0.2052264332],
+    [1.0000000000, -0.1138764855, 0.1332171584],
+    [1.0000000000, 0.0326151099, -0.6768871831],
+];
+
+/// Ebner and Fairchild's IPT, defined on XYZ relative to a D65 white with a luminance of 1.
+#[deriving(Show)]
+pub struct ColorIpt<T> {
+    pub i: T,
+    pub p: T,
+    pub t: T,
+}
+
+components!(ColorIpt { i, p, t })
+
+impl ColorIpt<f64> {
+    pub fn from_xyz(c: &ColorXyz) -> ColorIpt<f64> {
+        let f = |x: f64| x.signum() * x.abs().powf(0.43);
+        let lms = matrix::transform(&XYZ_TO_LMS, &c.to_array());
+        let ipt = matrix::transform(&LMS_TO_IPT, &[f(lms[0]), f(lms[1]), f(lms[2])]);
+        ColorIpt {
+            i: ipt[0],
+            p: ipt[1],
+            t: ipt[2],
+        }
+    }
+    pub fn to_xyz(&self) -> ColorXyz {
+        let f = |x: f64| x.signum() * x.abs().powf(0.43f64.recip());
+        let lms = matrix::transform(&IPT_TO_LMS, &[self.i, self.p, self.t]);
+        Color3::from_array(&matrix::transform(&LMS_TO_XYZ, &[f(lms[0]), f(lms[1]), f(lms[2])]))
+    }
+}
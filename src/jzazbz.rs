@@ -0,0 +1,118 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use matrix::{mod, Matrix3};
+use {Color3, ColorXyz};
+
+static B: f64 = 1.15;
+static G: f64 = 0.66;
+static C1: f64 = 3424. / 4096.;
+static C2: f64 = 2413. / 128.;
+static C3: f64 = 2392. / 128.;
+static N: f64 = 2610. / 16384.;
+static P: f64 = 1.7 * 2523. / 32.;
+static D: f64 = -0.56;
+static D0: f64 = 1.6295499532821566e-11;
+
+pub static XYZ_TO_LMS: Matrix3 = [
+    [0.41478972, 0.579999, 0.0146480],
+    [-0.2015100, 1.120649, 0.0531008],
+    [-0.0166008, 0.264800, 0.6684799],
+];
+
+pub static LMS_TO_XYZ: Matrix3 = [
+    [1.9242264358, -1.0047923126, 0.0376514040],
+    [0.3503167621, 0.7264811939, -0.0653844229],
+    [-0.0909828110, -0.3127282905, 1.5227665613],
+];
+
+pub static LMS_TO_IAB: Matrix3 = [
+    [0.5, 0.5, 0.],
+    [3.524000, -4.066708, 0.542708],
+    [0.199076, 1.096799, -1.295875],
+];
+
+pub static IAB_TO_LMS: Matrix3 = [
+    [1.0000000000, 0.1386050433, 0.0580473162],
+    [1.0000000000, -0.1386050433, -0.0580473162],
+    [1.0000000000, -0.0960192420, -0.8118918961],
+];
+
+/// Jzazbz by Safdar et al, computed from absolute D65 XYZ in cd/m².
+#[deriving(Show)]
+pub struct ColorJzazbz<T> {
+    pub jz: T,
+    pub az: T,
+    pub bz: T,
+}
+
+components!(ColorJzazbz { jz, az, bz })
+
+impl ColorJzazbz<f64> {
+    /// Converts XYZ where a luminance of 1 corresponds to `luminance` cd/m².
+    pub fn from_xyz(c: &ColorXyz, luminance: f64) -> ColorJzazbz<f64> {
+        let Color3(x, y, z) = *c * luminance;
+        let xp = B * x - (B - 1.) * z;
+        let yp = G * y - (G - 1.) * x;
+        let lms = matrix::transform(&XYZ_TO_LMS, &[xp, yp, z]);
+        let f = |x: f64| {
+            let x = (x / 10000.).max(0.).powf(N);
+            ((C1 + C2 * x) / (1. + C3 * x)).powf(P)
+        };
+        let iab = matrix::transform(&LMS_TO_IAB, &[f(lms[0]), f(lms[1]), f(lms[2])]);
+        ColorJzazbz {
+            jz: (1. + D) * iab[0] / (1. + D * iab[0]) - D0,
+            az: iab[1],
+            bz: iab[2],
+        }
+    }
+    /// Converts back to XYZ where a luminance of 1 corresponds to `luminance` cd/m².
+    pub fn to_xyz(&self, luminance: f64) -> ColorXyz {
+        let j = self.jz + D0;
+        let iz = j / (1. + D - D * j);
+        let lms = matrix::transform(&IAB_TO_LMS, &[iz, self.az, self.bz]);
+        let f = |x: f64| {
+            let x = x.max(0.).powf(P.recip());
+            10000. * ((C1 - x) / (C3 * x - C2)).max(0.).powf(N.recip())
+        };
+        let xyz = matrix::transform(&LMS_TO_XYZ, &[f(lms[0]), f(lms[1]), f(lms[2])]);
+        let x = (xyz[0] + (B - 1.) * xyz[2]) / B;
+        let y = (xyz[1] + (G - 1.) * x) / G;
+        Color3(x, y, xyz[2]) * luminance.recip()
+    }
+    pub fn to_jzczhz(&self) -> ColorJzczhz<f64> {
+        let (c, h) = ::to_polar(self.az, self.bz);
+        ColorJzczhz {
+            jz: self.jz,
+            cz: c,
+            hz: h,
+        }
+    }
+}
+
+/// The cylindrical form of `ColorJzazbz`, with hue in degrees.
+#[deriving(Show)]
+pub struct ColorJzczhz<T> {
+    pub jz: T,
+    pub cz: T,
+    pub hz: T,
+}
+
+components!(ColorJzczhz { jz, cz, hz })
+
+impl ColorJzczhz<f64> {
+    pub fn from_xyz(c: &ColorXyz, luminance: f64) -> ColorJzczhz<f64> {
+        ColorJzazbz::from_xyz(c, luminance).to_jzczhz()
+    }
+    pub fn to_xyz(&self, luminance: f64) -> ColorXyz {
+        self.to_jzazbz().to_xyz(luminance)
+    }
+    pub fn to_jzazbz(&self) -> ColorJzazbz<f64> {
+        let (a, b) = ::from_polar(self.cz, self.hz);
+        ColorJzazbz {
+            jz: self.jz,
+            az: a,
+            bz: b,
+        }
+    }
+}
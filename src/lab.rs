@@ -0,0 +1,86 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use {Color3, ColorXyy, ColorXyz};
+
+pub static EPSILON: f64 = 216. / 24389.;
+pub static KAPPA: f64 = 24389. / 27.;
+
+/// CIE 1976 L*a*b* relative to a reference white.
+#[deriving(Show)]
+pub struct ColorLab<T> {
+    pub l: T,
+    pub a: T,
+    pub b: T,
+}
+
+components!(ColorLab { l, a, b })
+
+impl ColorLab<f64> {
+    pub fn from_xyz(c: &ColorXyz, white: &ColorXyy<f64>) -> ColorLab<f64> {
+        fn f(t: f64) -> f64 {
+            if t > EPSILON {
+                t.cbrt()
+            } else {
+                (KAPPA * t + 16.) / 116.
+            }
+        }
+        let (&Color3(x, y, z), Color3(wx, wy, wz)) = (c, white.to_xyz());
+        let (fx, fy, fz) = (f(x / wx), f(y / wy), f(z / wz));
+        ColorLab {
+            l: 116. * fy - 16.,
+            a: 500. * (fx - fy),
+            b: 200. * (fy - fz),
+        }
+    }
+    pub fn to_xyz(&self, white: &ColorXyy<f64>) -> ColorXyz {
+        fn f(t: f64) -> f64 {
+            let t3 = t * t * t;
+            if t3 > EPSILON {
+                t3
+            } else {
+                (116. * t - 16.) / KAPPA
+            }
+        }
+        let Color3(wx, wy, wz) = white.to_xyz();
+        let fy = (self.l + 16.) / 116.;
+        let (fx, fz) = (fy + self.a / 500., fy - self.b / 200.);
+        let y = if self.l > KAPPA * EPSILON { fy * fy * fy } else { self.l / KAPPA };
+        Color3(f(fx) * wx, y * wy, f(fz) * wz)
+    }
+    pub fn to_lch(&self) -> ColorLch<f64> {
+        let (c, h) = ::to_polar(self.a, self.b);
+        ColorLch {
+            l: self.l,
+            c: c,
+            h: h,
+        }
+    }
+}
+
+/// The cylindrical form of `ColorLab`, with hue in degrees.
+#[deriving(Show)]
+pub struct ColorLch<T> {
+    pub l: T,
+    pub c: T,
+    pub h: T,
+}
+
+components!(ColorLch { l, c, h })
+
+impl ColorLch<f64> {
+    pub fn from_xyz(c: &ColorXyz, white: &ColorXyy<f64>) -> ColorLch<f64> {
+        ColorLab::from_xyz(c, white).to_lch()
+    }
+    pub fn to_xyz(&self, white: &ColorXyy<f64>) -> ColorXyz {
+        self.to_lab().to_xyz(white)
+    }
+    pub fn to_lab(&self) -> ColorLab<f64> {
+        let (a, b) = ::from_polar(self.c, self.h);
+        ColorLab {
+            l: self.l,
+            a: a,
+            b: b,
+        }
+    }
+}
@@ -1,10 +1,55 @@
 // Copyright © 2014, Peter Atashian
 
+#![feature(macro_rules)]
+
 extern crate term;
 
 use std::num::{Zero};
+use adaptation::{AdaptationMethod, ChromaticAdaptation};
+use component::Component;
+use matrix::Matrix3;
+use transfer::TransferFunction;
+use unit::{Encoded, Linear, Space};
 
+/// Gives a color model with fields of type `T` a `cast` to other component types. The module
+/// invoking it must import `Component`.
+macro_rules! components {
+    ($name:ident { $($field:ident),+ }) => (
+        impl<T> $name<T> where T: Component {
+            /// Converts to another component type, such as `f64` to `f32` or `Half`.
+            pub fn cast<V>(&self) -> $name<V> where V: Component {
+                $name { $($field: Component::from_unit(self.$field.to_unit())),+ }
+            }
+        }
+    )
+}
+
+pub mod adaptation;
+pub mod alpha;
+pub mod cam16;
+pub mod cmyk;
+pub mod component;
+pub mod composite;
+pub mod difference;
+pub mod din99;
+pub mod frame;
+pub mod gamut;
+pub mod hsl;
+pub mod hunter;
+pub mod ictcp;
+pub mod ipt;
+pub mod jzazbz;
+pub mod lab;
+pub mod lms;
+pub mod luv;
+pub mod matrix;
+pub mod oklab;
+pub mod osa;
 pub mod tables;
+pub mod transfer;
+pub mod unit;
+pub mod y4m;
+pub mod ycbcr;
 
 pub static SRGB: ColorSpace = ColorSpace {
     r: ColorXyy {
@@ -27,254 +72,580 @@ pub static SRGB: ColorSpace = ColorSpace {
         y: 0.3290,
         Y: 1.0000,
     },
+    transfer: TransferFunction::Srgb,
+};
+
+/// Display P3, the DCI-P3 primaries with a D65 white.
+pub static DISPLAY_P3: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.6800,
+        y: 0.3200,
+        Y: 0.2289745641,
+    },
+    g: ColorXyy {
+        x: 0.2650,
+        y: 0.6900,
+        Y: 0.6917385218,
+    },
+    b: ColorXyy {
+        x: 0.1500,
+        y: 0.0600,
+        Y: 0.0792869141,
+    },
+    w: ColorXyy {
+        x: 0.3127,
+        y: 0.3290,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::Srgb,
+};
+
+/// DCI-P3 as used in digital cinema projection, with the DCI white.
+pub static DCI_P3: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.6800,
+        y: 0.3200,
+        Y: 0.2094916779,
+    },
+    g: ColorXyy {
+        x: 0.2650,
+        y: 0.6900,
+        Y: 0.7215952542,
+    },
+    b: ColorXyy {
+        x: 0.1500,
+        y: 0.0600,
+        Y: 0.0689130679,
+    },
+    w: ColorXyy {
+        x: 0.3140,
+        y: 0.3510,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::Gamma(2.6),
+};
+
+/// Adobe RGB (1998).
+pub static ADOBE_RGB: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.6400,
+        y: 0.3300,
+        Y: 0.2973449753,
+    },
+    g: ColorXyy {
+        x: 0.2100,
+        y: 0.7100,
+        Y: 0.6273635663,
+    },
+    b: ColorXyy {
+        x: 0.1500,
+        y: 0.0600,
+        Y: 0.0752914585,
+    },
+    w: ColorXyy {
+        x: 0.3127,
+        y: 0.3290,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::AdobeRgb,
+};
+
+/// ITU-R BT.709, which shares its primaries and white with sRGB.
+pub static REC709: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.6400,
+        y: 0.3300,
+        Y: 0.2126,
+    },
+    g: ColorXyy {
+        x: 0.3000,
+        y: 0.6000,
+        Y: 0.7152,
+    },
+    b: ColorXyy {
+        x: 0.1500,
+        y: 0.0600,
+        Y: 0.0722,
+    },
+    w: ColorXyy {
+        x: 0.3127,
+        y: 0.3290,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::Rec709,
+};
+
+/// ITU-R BT.2020.
+pub static REC2020: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.7080,
+        y: 0.2920,
+        Y: 0.2627,
+    },
+    g: ColorXyy {
+        x: 0.1700,
+        y: 0.7970,
+        Y: 0.6780,
+    },
+    b: ColorXyy {
+        x: 0.1310,
+        y: 0.0460,
+        Y: 0.0593,
+    },
+    w: ColorXyy {
+        x: 0.3127,
+        y: 0.3290,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::Rec709,
+};
+
+/// ProPhoto RGB (ROMM RGB), with a D50 white.
+pub static PROPHOTO_RGB: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.734699,
+        y: 0.265301,
+        Y: 0.2880748288,
+    },
+    g: ColorXyy {
+        x: 0.159597,
+        y: 0.840403,
+        Y: 0.7118352342,
+    },
+    b: ColorXyy {
+        x: 0.036598,
+        y: 0.000105,
+        Y: 0.0000899369,
+    },
+    w: ColorXyy {
+        x: 0.3457,
+        y: 0.3585,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::ProPhoto,
+};
+
+/// ACEScg, the AP1 primaries with the ACES white.
+pub static ACESCG: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.7130,
+        y: 0.2930,
+        Y: 0.2722287168,
+    },
+    g: ColorXyy {
+        x: 0.1650,
+        y: 0.8300,
+        Y: 0.6740817658,
+    },
+    b: ColorXyy {
+        x: 0.1280,
+        y: 0.0440,
+        Y: 0.0536895174,
+    },
+    w: ColorXyy {
+        x: 0.32168,
+        y: 0.33767,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::Linear,
+};
+
+/// ACES2065-1, the AP0 primaries with the ACES white.
+pub static ACES2065_1: ColorSpace = ColorSpace {
+    r: ColorXyy {
+        x: 0.7347,
+        y: 0.2653,
+        Y: 0.3439664498,
+    },
+    g: ColorXyy {
+        x: 0.0000,
+        y: 1.0000,
+        Y: 0.7281660966,
+    },
+    b: ColorXyy {
+        x: 0.0001,
+        y: -0.0770,
+        Y: -0.0721325464,
+    },
+    w: ColorXyy {
+        x: 0.32168,
+        y: 0.33767,
+        Y: 1.0000,
+    },
+    transfer: TransferFunction::Linear,
+};
+
+pub static D65: ColorXyy<f64> = ColorXyy {
+    x: 0.3127,
+    y: 0.3290,
+    Y: 1.0000,
+};
+
+pub static D50: ColorXyy<f64> = ColorXyy {
+    x: 0.3457,
+    y: 0.3585,
+    Y: 1.0000,
+};
+
+pub static DCI_WHITE: ColorXyy<f64> = ColorXyy {
+    x: 0.3140,
+    y: 0.3510,
+    Y: 1.0000,
+};
+
+pub static ACES_WHITE: ColorXyy<f64> = ColorXyy {
+    x: 0.32168,
+    y: 0.33767,
+    Y: 1.0000,
 };
 
 #[deriving(Show)]
 pub struct ColorSpace {
-    pub r: ColorXyy,
-    pub g: ColorXyy,
-    pub b: ColorXyy,
-    pub w: ColorXyy,
+    pub r: ColorXyy<f64>,
+    pub g: ColorXyy<f64>,
+    pub b: ColorXyy<f64>,
+    pub w: ColorXyy<f64>,
+    pub transfer: TransferFunction,
 }
 
-#[deriving(Show)]
-pub struct ColorXyz {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+#[deriving(Show, PartialEq)]
+pub enum ColorSpaceError {
+    /// The white point has a non-positive `y` chromaticity.
+    InvalidWhite,
+    /// The three primaries are collinear and do not span a gamut.
+    DegeneratePrimaries,
+    /// The white point does not lie strictly inside the triangle of the primaries.
+    WhiteOutsideGamut,
 }
 
-impl ColorXyz {
-    pub fn from_array(arr: &[f64, ..3]) -> ColorXyz {
-        ColorXyz {
-            x: arr[0],
-            y: arr[1],
-            z: arr[2],
+impl ColorSpace {
+    /// Builds a color space from the xy chromaticities of its primaries and white point,
+    /// deriving the `Y` of each primary so that they sum to the white.
+    pub fn from_primaries(r: (f64, f64), g: (f64, f64), b: (f64, f64), w: (f64, f64),
+                          transfer: TransferFunction) -> Result<ColorSpace, ColorSpaceError> {
+        let ((xr, yr), (xg, yg), (xb, yb), (xw, yw)) = (r, g, b, w);
+        if !(yw > 0.) {
+            return Err(ColorSpaceError::InvalidWhite)
         }
-    }
-    pub fn from_wavelength(wavelength: uint) -> ColorXyz {
-        use self::tables::CIE_COLOR_MATCH;
-        match CIE_COLOR_MATCH.get(wavelength - 390) {
-            Some(c) => ColorXyz::from_array(c),
-            None => Zero::zero(),
+        let primaries = [
+            [xr, xg, xb],
+            [yr, yg, yb],
+            [1. - (xr + yr), 1. - (xg + yg), 1. - (xb + yb)],
+        ];
+        if matrix::determinant(&primaries).abs() < 1e-12 {
+            return Err(ColorSpaceError::DegeneratePrimaries)
         }
-    }
-    pub fn to_rgb(&self, cs: &ColorSpace) -> ColorRgbF64 {
-        let (xc, yc, zc) = (self.x, self.y, self.z);
-        let (xr, yr, zr) = (cs.r.x, cs.r.y, 1. - (cs.r.x + cs.r.y));
-        let (xg, yg, zg) = (cs.g.x, cs.g.y, 1. - (cs.g.x + cs.g.y));
-        let (xb, yb, zb) = (cs.b.x, cs.b.y, 1. - (cs.b.x + cs.b.y));
-        let (xw, yw, zw) = (cs.w.x, cs.w.y, 1. - (cs.w.x + cs.w.y));
-        let (rx, ry, rz) = (yg * zb - yb * zg, xb * zg - xg * zb, xg * yb - xb * yg);
-        let (gx, gy, gz) = (yb * zr - yr * zb, xr * zb - xb * zr, xb * yr - xr * yb);
-        let (bx, by, bz) = (yr * zg - yg * zr, xg * zr - xr * zg, xr * yg - xg * yr);
-        let rw = (rx * xw + ry * yw + rz * zw) / yw;
-        let gw = (gx * xw + gy * yw + gz * zw) / yw;
-        let bw = (bx * xw + by * yw + bz * zw) / yw;
-        let (rx, ry, rz) = (rx / rw, ry / rw, rz / rw);
-        let (gx, gy, gz) = (gx / gw, gy / gw, gz / gw);
-        let (bx, by, bz) = (bx / bw, by / bw, bz / bw);
-        ColorRgbF64 {
-            r: rx * xc + ry * yc + rz * zc,
-            g: gx * xc + gy * yc + gz * zc,
-            b: bx * xc + by * yc + bz * zc,
+        let inv = match matrix::invert(&primaries) {
+            Some(inv) => inv,
+            None => return Err(ColorSpaceError::DegeneratePrimaries),
+        };
+        let weights = matrix::transform(&inv, &[xw, yw, 1. - (xw + yw)]);
+        if weights.iter().any(|&s| !(s > 0.)) {
+            return Err(ColorSpaceError::WhiteOutsideGamut)
         }
+        let mut cs = ColorSpace {
+            r: ColorXyy { x: xr, y: yr, Y: 0. },
+            g: ColorXyy { x: xg, y: yg, Y: 0. },
+            b: ColorXyy { x: xb, y: yb, Y: 0. },
+            w: ColorXyy { x: xw, y: yw, Y: 1. },
+            transfer: transfer,
+        };
+        let lum = cs.to_xyz_matrix()[1];
+        cs.r.Y = lum[0];
+        cs.g.Y = lum[1];
+        cs.b.Y = lum[2];
+        Ok(cs)
     }
-    pub fn normalize(&self) -> ColorXyz {
-        let m = self.x.max(self.y).max(self.z);
-        ColorXyz {
-            x: self.x.div(&m),
-            y: self.y.div(&m),
-            z: self.z.div(&m),
+    /// The xyz chromaticities of the primaries, one row each, and the cofactors of that matrix
+    /// scaled so that each row maps the white to 1.
+    fn cofactors(&self) -> (Matrix3, Matrix3) {
+        let xyz = |c: &ColorXyy<f64>| [c.x, c.y, 1. - (c.x + c.y)];
+        let (r, g, b, w) = (xyz(&self.r), xyz(&self.g), xyz(&self.b), xyz(&self.w));
+        let cross = |p: &[f64, ..3], q: &[f64, ..3]| {
+            [p[1] * q[2] - p[2] * q[1], p[2] * q[0] - p[0] * q[2], p[0] * q[1] - p[1] * q[0]]
+        };
+        let mut cof = [cross(&g, &b), cross(&b, &r), cross(&r, &g)];
+        for row in cof.iter_mut() {
+            let s = (row[0] * w[0] + row[1] * w[1] + row[2] * w[2]) / w[1];
+            for v in row.iter_mut() {
+                *v /= s;
+            }
         }
+        ([r, g, b], cof)
     }
-}
-
-impl Zero for ColorXyz {
-    fn zero() -> ColorXyz {
-        ColorXyz {
-            x: 0.,
-            y: 0.,
-            z: 0.,
+    /// The matrix from linear RGB to XYZ.
+    pub fn to_xyz_matrix(&self) -> Matrix3 {
+        let (p, cof) = self.cofactors();
+        // The scaled cofactors are the rows of the inverse, so each primary is scaled by the
+        // reciprocal of its dot product with its own row.
+        let mut m = [[0f64, ..3], ..3];
+        for i in range(0u, 3) {
+            let s = (p[i][0] * cof[i][0] + p[i][1] * cof[i][1] + p[i][2] * cof[i][2]).recip();
+            for j in range(0u, 3) {
+                m[j][i] = p[i][j] * s;
+            }
         }
+        m
     }
-    fn is_zero(&self) -> bool {
-        self.x == 0. && self.y == 0. && self.z == 0.
+    /// The matrix from XYZ to linear RGB.
+    pub fn to_rgb_matrix(&self) -> Matrix3 {
+        let (_, cof) = self.cofactors();
+        cof
     }
 }
 
-impl Add<ColorXyz, ColorXyz> for ColorXyz {
-    fn add(&self, o: &ColorXyz) -> ColorXyz {
-        ColorXyz {
-            x: self.x + o.x,
-            y: self.y + o.y,
-            z: self.z + o.z,
-        }
-    }
+/// The RGB to XYZ matrix of the color space `S` and its inverse, computed once so that bulk
+/// conversions only cost a matrix multiply per color.
+#[deriving(Show)]
+pub struct ColorSpaceMatrix<S> {
+    pub to_xyz: Matrix3,
+    pub to_rgb: Matrix3,
 }
-impl Mul<f64, ColorXyz> for ColorXyz {
-    fn mul(&self, o: &f64) -> ColorXyz {
-        ColorXyz {
-            x: self.x.mul(o),
-            y: self.y.mul(o),
-            z: self.z.mul(o),
+
+impl<S> ColorSpaceMatrix<S> where S: Space {
+    pub fn new() -> ColorSpaceMatrix<S> {
+        let cs = unit::space_of::<S>();
+        ColorSpaceMatrix {
+            to_xyz: cs.to_xyz_matrix(),
+            to_rgb: cs.to_rgb_matrix(),
         }
     }
+    pub fn to_rgb(&self, c: &ColorXyz) -> Color3<f64, Linear<S>> {
+        Color3::from_array(&matrix::transform(&self.to_rgb, &c.to_array()))
+    }
+    pub fn to_xyz(&self, c: &Color3<f64, Linear<S>>) -> ColorXyz {
+        Color3::from_array(&matrix::transform(&self.to_xyz, &c.to_array()))
+    }
 }
 
-#[allow(non_snake_case)]
-#[deriving(Show)]
-pub struct ColorXyy {
-    pub x: f64,
-    pub y: f64,
-    pub Y: f64,
-}
-
+/// A conversion of RGB values from the color space `S` to `D`, composing both color space
+/// matrices with a chromatic adaptation between their white points.
 #[deriving(Show)]
-pub struct ColorRgbU8 {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
+pub struct RgbConversion<S, D> {
+    pub matrix: Matrix3,
 }
 
-impl ColorRgbU8 {
-    pub fn to_float(&self) -> ColorRgbF64 {
-        ColorRgbF64 {
-            r: self.r as f64 / 255.,
-            g: self.g as f64 / 255.,
-            b: self.b as f64 / 255.,
+impl<S, D> RgbConversion<S, D> where S: Space, D: Space {
+    pub fn new(method: AdaptationMethod) -> RgbConversion<S, D> {
+        let (from, to) = (unit::space_of::<S>(), unit::space_of::<D>());
+        let cat = ChromaticAdaptation::new(method, &from.w.to_xyz(), &to.w.to_xyz());
+        let m = matrix::mul(&cat.matrix, &from.to_xyz_matrix());
+        RgbConversion {
+            matrix: matrix::mul(&to.to_rgb_matrix(), &m),
         }
     }
+    /// Converts linear RGB values.
+    pub fn convert(&self, c: &Color3<f64, Linear<S>>) -> Color3<f64, Linear<D>> {
+        Color3::from_array(&matrix::transform(&self.matrix, &c.to_array()))
+    }
+    /// Converts values encoded with the transfer function of the source color space into
+    /// values encoded with that of the destination.
+    pub fn convert_encoded(&self, c: &Color3<f64, Encoded<S>>) -> Color3<f64, Encoded<D>> {
+        self.convert(&c.decode()).encode()
+    }
 }
 
-#[deriving(Show)]
-pub struct ColorRgbF64 {
-    pub r: f64,
-    pub g: f64,
-    pub b: f64,
+/// Converts cartesian opponent coordinates to chroma and a hue in degrees within [0, 360).
+fn to_polar(a: f64, b: f64) -> (f64, f64) {
+    let h = b.atan2(a).to_degrees();
+    (a.hypot(b), if h < 0. { h + 360. } else { h })
+}
+
+fn from_polar(c: f64, h: f64) -> (f64, f64) {
+    let h = h.to_radians();
+    (c * h.cos(), c * h.sin())
 }
 
-impl ColorRgbF64 {
-    pub fn to_int(&self) -> ColorRgbU8 {
-        ColorRgbU8 {
-            r: (self.r.min(1.).max(0.) * 255.).round() as u8,
-            g: (self.g.min(1.).max(0.) * 255.).round() as u8,
-            b: (self.b.min(1.).max(0.) * 255.).round() as u8,
+/// XYZ tristimulus values, which is what the other color models convert through.
+pub type ColorXyz = Color3<f64, unit::Xyz>;
+
+impl Color3<f64, unit::Xyz> {
+    pub fn from_wavelength(wavelength: uint) -> ColorXyz {
+        use self::tables::CIE_COLOR_MATCH;
+        match CIE_COLOR_MATCH.get(wavelength - 390) {
+            Some(c) => Color3::from_array(c),
+            None => Zero::zero(),
         }
     }
-    pub fn luminance(&self, cs: &ColorSpace) -> f64 {
-        self.r * cs.r.Y + self.g * cs.g.Y + self.b * cs.b.Y
-    }
-    pub fn normalize(&self) -> ColorRgbF64 {
-        let m = self.r.max(self.g).max(self.b);
-        ColorRgbF64 {
-            r: self.r.div(&m),
-            g: self.g.div(&m),
-            b: self.b.div(&m),
+    /// Black has no chromaticity and maps to zero chromaticity coordinates.
+    pub fn to_xyy(&self) -> ColorXyy<f64> {
+        let &Color3(x, y, z) = self;
+        let sum = x + y + z;
+        if sum == 0. {
+            return ColorXyy { x: 0., y: 0., Y: y }
         }
-    }
-    pub fn constrain(&self) -> ColorRgbF64 {
-        let w = 0f64.min(self.r).min(self.g).min(self.b);
-        ColorRgbF64 {
-            r: self.r - w,
-            g: self.g - w,
-            b: self.b - w,
+        ColorXyy {
+            x: x / sum,
+            y: y / sum,
+            Y: y,
         }
     }
-    pub fn encode_srgb(&self) -> ColorRgbF64 {
-        fn encode(x: f64) -> f64 {
-            if x <= 0.0031308 {
-                x * 12.92
-            } else {
-                x.powf(2.4f64.recip()) * (1. + 0.055) - 0.055
-            }
+    pub fn to_uv1976(&self) -> ColorUv1976<f64> {
+        let &Color3(x, y, z) = self;
+        let d = x + 15. * y + 3. * z;
+        if d == 0. {
+            return ColorUv1976 { u: 0., v: 0. }
         }
-        ColorRgbF64 {
-            r: encode(self.r),
-            g: encode(self.g),
-            b: encode(self.b),
+        ColorUv1976 {
+            u: 4. * x / d,
+            v: 9. * y / d,
         }
     }
-    pub fn decode_srgb(&self) -> ColorRgbF64 {
-        fn decode(x: f64) -> f64 {
-            if x <= 0.04045 {
-                x / 12.92
-            } else {
-                ((x + 0.055) / (1. + 0.055)).powf(2.4)
-            }
-        }
-        ColorRgbF64 {
-            r: decode(self.r),
-            g: decode(self.g),
-            b: decode(self.b),
-        }
+    pub fn adapt(&self, src: &ColorXyz, dst: &ColorXyz, method: AdaptationMethod) -> ColorXyz {
+        ChromaticAdaptation::new(method, src, dst).apply(self)
     }
-    pub fn from_hue(hue: f64) -> ColorRgbF64 {
-        let x = 1. - (hue % 2. - 1.).abs();
-        match hue {
-            h if h >= 0. && h < 1. => ColorRgbF64 { r: 1., g: x, b: 0. },
-            h if h >= 1. && h < 2. => ColorRgbF64 { r: x, g: 1., b: 0. },
-            h if h >= 2. && h < 3. => ColorRgbF64 { r: 0., g: 1., b: x },
-            h if h >= 3. && h < 4. => ColorRgbF64 { r: 0., g: x, b: 1. },
-            h if h >= 4. && h < 5. => ColorRgbF64 { r: x, g: 0., b: 1. },
-            h if h >= 5. && h < 6. => ColorRgbF64 { r: 1., g: 0., b: x },
-            _ => unreachable!(),
+}
+
+#[allow(non_snake_case)]
+#[deriving(Show)]
+pub struct ColorXyy<T> {
+    pub x: T,
+    pub y: T,
+    pub Y: T,
+}
+
+components!(ColorXyy { x, y, Y })
+
+impl ColorXyy<f64> {
+    /// A chromaticity with `y` of zero carries no luminance and maps to black.
+    pub fn to_xyz(&self) -> ColorXyz {
+        if self.y == 0. {
+            return Zero::zero()
         }
+        Color3(self.x * self.Y / self.y, self.Y, (1. - self.x - self.y) * self.Y / self.y)
     }
-    pub fn target_luminance(&self, lum: f64, cs: &ColorSpace) -> ColorRgbF64 {
-        let l = self.luminance(cs);
-        if l < lum {
-            let d = (lum - 1.) / (l - 1.);
-            self * d + ColorRgbF64::white() * (1. - d)
-        } else {
-            self * (lum / l)
+    pub fn to_uv1960(&self) -> ColorUv1960<f64> {
+        let d = -2. * self.x + 12. * self.y + 3.;
+        ColorUv1960 {
+            u: 4. * self.x / d,
+            v: 6. * self.y / d,
         }
     }
-    pub fn white() -> ColorRgbF64 {
-        ColorRgbF64 {
-            r: 1.,
-            g: 1.,
-            b: 1.,
+    pub fn to_uv1976(&self) -> ColorUv1976<f64> {
+        let d = -2. * self.x + 12. * self.y + 3.;
+        ColorUv1976 {
+            u: 4. * self.x / d,
+            v: 9. * self.y / d,
         }
     }
 }
 
-impl Add<ColorRgbF64, ColorRgbF64> for ColorRgbF64 {
-    fn add(&self, o: &ColorRgbF64) -> ColorRgbF64 {
-        ColorRgbF64 {
-            r: self.r.add(&o.r),
-            g: self.g.add(&o.g),
-            b: self.b.add(&o.b),
-        }
-    }
+/// CIE 1960 UCS chromaticity coordinates.
+#[deriving(Show)]
+pub struct ColorUv1960<T> {
+    pub u: T,
+    pub v: T,
 }
 
-impl Mul<f64, ColorRgbF64> for ColorRgbF64 {
-    fn mul(&self, o: &f64) -> ColorRgbF64 {
-        ColorRgbF64 {
-            r: self.r.mul(o),
-            g: self.g.mul(o),
-            b: self.b.mul(o),
+components!(ColorUv1960 { u, v })
+
+impl ColorUv1960<f64> {
+    pub fn to_xyy(&self, lum: f64) -> ColorXyy<f64> {
+        let d = 2. * self.u - 8. * self.v + 4.;
+        ColorXyy {
+            x: 3. * self.u / d,
+            y: 2. * self.v / d,
+            Y: lum,
         }
     }
+    pub fn to_uv1976(&self) -> ColorUv1976<f64> {
+        ColorUv1976 {
+            u: self.u,
+            v: self.v * 1.5,
+        }
+    }
+}
+
+/// CIE 1976 UCS chromaticity coordinates, u' and v'.
+#[deriving(Show)]
+pub struct ColorUv1976<T> {
+    pub u: T,
+    pub v: T,
 }
 
-impl Div<f64, ColorRgbF64> for ColorRgbF64 {
-    fn div(&self, o: &f64) -> ColorRgbF64 {
-        ColorRgbF64 {
-            r: self.r.div(o),
-            g: self.g.div(o),
-            b: self.b.div(o),
+components!(ColorUv1976 { u, v })
+
+impl ColorUv1976<f64> {
+    pub fn to_xyy(&self, lum: f64) -> ColorXyy<f64> {
+        let d = 6. * self.u - 16. * self.v + 12.;
+        ColorXyy {
+            x: 9. * self.u / d,
+            y: 4. * self.v / d,
+            Y: lum,
+        }
+    }
+    pub fn to_uv1960(&self) -> ColorUv1960<f64> {
+        ColorUv1960 {
+            u: self.u,
+            v: self.v / 1.5,
         }
     }
 }
 
+/// RGB in the unit `U`, such as `unit::Linear<unit::Srgb>`, which records the color space and
+/// whether the values are linear or encoded.
+pub type ColorRgbF64<U> = Color3<f64, U>;
+pub type ColorRgbU8<U> = Color3<u8, U>;
+
+/// Three components of type `T` in the unit `U`, one of the markers in `unit`.
 #[deriving(Show)]
-pub struct Color3<T, U>(T, T, T);
+pub struct Color3<T, U>(pub T, pub T, pub T);
 
+impl<T, U> Color3<T, U> where T: Copy {
+    pub fn from_array(arr: &[T, ..3]) -> Color3<T, U> {
+        Color3(arr[0], arr[1], arr[2])
+    }
+    pub fn to_array(&self) -> [T, ..3] {
+        let &Color3(a, b, c) = self;
+        [a, b, c]
+    }
+}
 impl<T, U> Color3<T, U> where T: Float {
     pub fn normalize(&self) -> Color3<T, U> {
-        unimplemented!()
+        let &Color3(a, b, c) = self;
+        let m = a.max(b).max(c);
+        Color3(a / m, b / m, c / m)
+    }
+}
+impl<T, U> Color3<T, U> where T: Component {
+    /// Converts to another component type, such as `u8` to `f32` or `f64` to `Half`.
+    pub fn cast<V>(&self) -> Color3<V, U> where V: Component {
+        let &Color3(a, b, c) = self;
+        Color3(Component::from_unit(a.to_unit()), Component::from_unit(b.to_unit()),
+               Component::from_unit(c.to_unit()))
+    }
+}
+impl<U> Color3<f64, U> where U: unit::Rgb {
+    /// The most saturated color of a hue in [0, 6), where 0 is red, 2 is green and 4 is blue.
+    pub fn from_hue(hue: f64) -> Color3<f64, U> {
+        let x = 1. - (hue % 2. - 1.).abs();
+        match hue {
+            h if h >= 0. && h < 1. => Color3(1., x, 0.),
+            h if h >= 1. && h < 2. => Color3(x, 1., 0.),
+            h if h >= 2. && h < 3. => Color3(0., 1., x),
+            h if h >= 3. && h < 4. => Color3(0., x, 1.),
+            h if h >= 4. && h < 5. => Color3(x, 0., 1.),
+            h if h >= 5. && h < 6. => Color3(1., 0., x),
+            _ => unreachable!(),
+        }
+    }
+    pub fn white() -> Color3<f64, U> {
+        Color3(1., 1., 1.)
+    }
+    /// Adds white until no channel is negative.
+    pub fn constrain(&self) -> Color3<f64, U> {
+        let &Color3(r, g, b) = self;
+        let w = 0f64.min(r).min(g).min(b);
+        Color3(r - w, g - w, b - w)
+    }
+}
+impl<T, U> Zero for Color3<T, U> where T: Zero {
+    fn zero() -> Color3<T, U> {
+        Color3(Zero::zero(), Zero::zero(), Zero::zero())
+    }
+    fn is_zero(&self) -> bool {
+        let &Color3(ref a, ref b, ref c) = self;
+        a.is_zero() && b.is_zero() && c.is_zero()
     }
 }
 impl<T, U> Mul<Color3<T, U>, Color3<T, U>> for Color3<T, U> where T: Mul<T, T> {
@@ -291,3 +662,66 @@ impl<T, U> Add<Color3<T, U>, Color3<T, U>> for Color3<T, U> where T: Add<T, T> {
         Color3(a1.add(b1), a2.add(b2), a3.add(b3))
     }
 }
+impl<U> Mul<f64, Color3<f64, U>> for Color3<f64, U> {
+    fn mul(&self, o: &f64) -> Color3<f64, U> {
+        let &Color3(a, b, c) = self;
+        Color3(a * *o, b * *o, c * *o)
+    }
+}
+impl<U> Div<f64, Color3<f64, U>> for Color3<f64, U> {
+    fn div(&self, o: &f64) -> Color3<f64, U> {
+        let &Color3(a, b, c) = self;
+        Color3(a / *o, b / *o, c / *o)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use component::Half;
+    use lab::ColorLab;
+    use matrix;
+    use transfer::TransferFunction;
+    use unit::{Linear, Srgb};
+    use {Color3, ColorSpace};
+
+    static SAMPLES: [[f64, ..3], ..4] = [
+        [0., 0., 0.], [1., 1., 1.], [0.2, 0.5, 0.9], [1.2, -0.1, 0.4],
+    ];
+
+    fn check(c: &[f64, ..3], back: &[f64, ..3]) {
+        for (a, b) in c.iter().zip(back.iter()) {
+            assert!((*a - *b).abs() < 1e-12, "{} {}", c, back);
+        }
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        for c in SAMPLES.iter() {
+            let rgb: Color3<f64, Linear<Srgb>> = Color3::from_array(c);
+            check(c, &rgb.to_xyz().to_linear::<Srgb>().to_array());
+        }
+    }
+
+    #[test]
+    fn custom_round_trip() {
+        let cs = ColorSpace::from_primaries((0.68, 0.32), (0.265, 0.69), (0.15, 0.06),
+                                            (0.3127, 0.329), TransferFunction::Linear).unwrap();
+        let (to_xyz, to_rgb) = (cs.to_xyz_matrix(), cs.to_rgb_matrix());
+        for c in SAMPLES.iter() {
+            check(c, &matrix::transform(&to_rgb, &matrix::transform(&to_xyz, c)));
+        }
+    }
+
+    #[test]
+    fn model_cast() {
+        // Every value here is exact in half precision, so narrowing gives them back unchanged.
+        let lab = ColorLab { l: 0.5, a: 0.25, b: -0.125 };
+        let half: ColorLab<Half> = lab.cast();
+        let back: ColorLab<f64> = half.cast();
+        assert!(back.l == lab.l && back.a == lab.a && back.b == lab.b, "{}", back);
+        let single = ColorLab { l: 0.1f32, a: -0.2, b: 0.3 };
+        let wide: ColorLab<f64> = single.cast();
+        let narrow: ColorLab<f32> = wide.cast();
+        assert!(narrow.l == single.l && narrow.a == single.a && narrow.b == single.b);
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright © 2014, Peter Atashian
+
+use adaptation::{BRADFORD, CAT02, HUNT_POINTER_ESTEVEZ};
+use component::Component;
+use matrix::{mod, Matrix3};
+use {Color3, ColorXyz};
+
+/// XYZ to the CIE 2006 2° cone fundamentals, the inverse of `CIE2006_TO_XYZ`.
+pub static XYZ_TO_CIE2006: Matrix3 = [
+    [0.2105758197, 0.8550976432, -0.0396982652],
+    [-0.4170763738, 1.1772610964, 0.0786282514],
+    [0.0000000000, 0.0000000000, 0.5168350142],
+];
+
+/// The CIE 2006 2° cone fundamentals to the XYZ color matching functions of
+/// `tables::CIE_COLOR_MATCH`.
+pub static CIE2006_TO_XYZ: Matrix3 = [
+    [1.94735469, -1.41445123, 0.36476327],
+    [0.68990272, 0.34832189, 0.00000000],
+    [0.00000000, 0.00000000, 1.93485343],
+];
+
+#[deriving(Show, PartialEq)]
+pub enum LmsMatrix {
+    HuntPointerEstevez,
+    Bradford,
+    Cat02,
+    Cie2006,
+}
+
+impl LmsMatrix {
+    pub fn from_xyz(&self) -> Matrix3 {
+        match *self {
+            LmsMatrix::HuntPointerEstevez => HUNT_POINTER_ESTEVEZ,
+            LmsMatrix::Bradford => BRADFORD,
+            LmsMatrix::Cat02 => CAT02,
+            LmsMatrix::Cie2006 => XYZ_TO_CIE2006,
+        }
+    }
+    pub fn to_xyz(&self) -> Matrix3 {
+        match *self {
+            LmsMatrix::Cie2006 => CIE2006_TO_XYZ,
+            _ => matrix::invert(&self.from_xyz()).expect("cone matrix must be invertible"),
+        }
+    }
+}
+
+/// Long, medium and short cone responses.
+#[deriving(Show)]
+pub struct ColorLms<T> {
+    pub l: T,
+    pub m: T,
+    pub s: T,
+}
+
+components!(ColorLms { l, m, s })
+
+impl ColorLms<f64> {
+    pub fn from_xyz(c: &ColorXyz, m: LmsMatrix) -> ColorLms<f64> {
+        let lms = matrix::transform(&m.from_xyz(), &c.to_array());
+        ColorLms {
+            l: lms[0],
+            m: lms[1],
+            s: lms[2],
+        }
+    }
+    pub fn to_xyz(&self, m: LmsMatrix) -> ColorXyz {
+        Color3::from_array(&matrix::transform(&m.to_xyz(), &[self.l, self.m, self.s]))
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright © 2014, Peter Atashian
+
+use std::f64;
+use std::num::{Zero};
+use component::Component;
+use lab::{EPSILON, KAPPA};
+use {Color3, ColorSpace, ColorXyy, ColorXyz};
+
+fn lightness(y: f64) -> f64 {
+    if y > EPSILON {
+        116. * y.cbrt() - 16.
+    } else {
+        KAPPA * y
+    }
+}
+
+fn inverse_lightness(l: f64) -> f64 {
+    if l > KAPPA * EPSILON {
+        ((l + 16.) / 116.).powi(3)
+    } else {
+        l / KAPPA
+    }
+}
+
+/// CIE 1976 L*u*v* relative to a reference white.
+#[deriving(Show)]
+pub struct ColorLuv<T> {
+    pub l: T,
+    pub u: T,
+    pub v: T,
+}
+
+components!(ColorLuv { l, u, v })
+
+impl ColorLuv<f64> {
+    pub fn from_xyz(c: &ColorXyz, white: &ColorXyy<f64>) -> ColorLuv<f64> {
+        let w = white.to_xyz();
+        let (&Color3(x, y, z), Color3(_, wy, _)) = (c, w);
+        let l = lightness(y / wy);
+        if x + 15. * y + 3. * z == 0. {
+            return ColorLuv { l: l, u: 0., v: 0. }
+        }
+        let (uv, uvn) = (c.to_uv1976(), w.to_uv1976());
+        ColorLuv {
+            l: l,
+            u: 13. * l * (uv.u - uvn.u),
+            v: 13. * l * (uv.v - uvn.v),
+        }
+    }
+    pub fn to_xyz(&self, white: &ColorXyy<f64>) -> ColorXyz {
+        if self.l <= 0. {
+            return Zero::zero()
+        }
+        let w = white.to_xyz();
+        let uvn = w.to_uv1976();
+        let u = self.u / (13. * self.l) + uvn.u;
+        let v = self.v / (13. * self.l) + uvn.v;
+        let Color3(_, wy, _) = w;
+        let y = inverse_lightness(self.l) * wy;
+        Color3(y * 9. * u / (4. * v), y, y * (12. - 3. * u - 20. * v) / (4. * v))
+    }
+    pub fn to_lchuv(&self) -> ColorLchuv<f64> {
+        let (c, h) = ::to_polar(self.u, self.v);
+        ColorLchuv {
+            l: self.l,
+            c: c,
+            h: h,
+        }
+    }
+}
+
+/// The cylindrical form of `ColorLuv`, with hue in degrees.
+#[deriving(Show)]
+pub struct ColorLchuv<T> {
+    pub l: T,
+    pub c: T,
+    pub h: T,
+}
+
+components!(ColorLchuv { l, c, h })
+
+impl ColorLchuv<f64> {
+    pub fn from_xyz(c: &ColorXyz, white: &ColorXyy<f64>) -> ColorLchuv<f64> {
+        ColorLuv::from_xyz(c, white).to_lchuv()
+    }
+    pub fn to_xyz(&self, white: &ColorXyy<f64>) -> ColorXyz {
+        self.to_luv().to_xyz(white)
+    }
+    pub fn to_luv(&self) -> ColorLuv<f64> {
+        let (u, v) = ::from_polar(self.c, self.h);
+        ColorLuv {
+            l: self.l,
+            u: u,
+            v: v,
+        }
+    }
+}
+
+/// The largest LCh(uv) chroma at the given lightness and hue that stays inside the gamut of a
+/// color space, measured relative to the white of that color space.
+pub fn max_chroma(l: f64, h: f64, cs: &ColorSpace) -> f64 {
+    let m = cs.to_rgb_matrix();
+    let w = cs.w.to_xyz();
+    let uvn = w.to_uv1976();
+    let Color3(_, wy, _) = w;
+    let y = inverse_lightness(l) * wy;
+    let (hs, hc) = h.to_radians().sin_cos();
+    let mut best = f64::INFINITY;
+    // Every channel is linear in chroma along a hue line, so each gamut face is crossed at a
+    // single chroma which can be solved for directly.
+    for row in m.iter() {
+        let a = 9. * row[0] - 3. * row[2];
+        for &t in [0f64, 1.].iter() {
+            let bv = 4. * row[1] * y - 20. * row[2] * y - 4. * t;
+            let den = y * a * hc + bv * hs;
+            if den == 0. {
+                continue
+            }
+            let k = -(y * a * uvn.u + 12. * row[2] * y + bv * uvn.v) / den;
+            let c = 13. * l * k;
+            if c >= 0. && c < best {
+                best = c;
+            }
+        }
+    }
+    best
+}
+
+/// HSLuv, an LCh(uv) whose saturation is the percentage of the largest chroma that fits in the
+/// gamut of a color space at that lightness and hue.
+#[deriving(Show)]
+pub struct ColorHsluv<T> {
+    pub h: T,
+    pub s: T,
+    pub l: T,
+}
+
+components!(ColorHsluv { h, s, l })
+
+impl ColorHsluv<f64> {
+    pub fn from_lchuv(c: &ColorLchuv<f64>, cs: &ColorSpace) -> ColorHsluv<f64> {
+        let s = if c.l > 99.9999999 || c.l < 1e-8 {
+            0.
+        } else {
+            c.c / max_chroma(c.l, c.h, cs) * 100.
+        };
+        ColorHsluv {
+            h: c.h,
+            s: s,
+            l: c.l,
+        }
+    }
+    pub fn to_lchuv(&self, cs: &ColorSpace) -> ColorLchuv<f64> {
+        let c = if self.l > 99.9999999 || self.l < 1e-8 {
+            0.
+        } else {
+            max_chroma(self.l, self.h, cs) * self.s / 100.
+        };
+        ColorLchuv {
+            l: self.l,
+            c: c,
+            h: self.h,
+        }
+    }
+    pub fn from_xyz(c: &ColorXyz, cs: &ColorSpace) -> ColorHsluv<f64> {
+        ColorHsluv::from_lchuv(&ColorLchuv::from_xyz(c, &cs.w), cs)
+    }
+    pub fn to_xyz(&self, cs: &ColorSpace) -> ColorXyz {
+        self.to_lchuv(cs).to_xyz(&cs.w)
+    }
+}
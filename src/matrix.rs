@@ -0,0 +1,61 @@
+// Copyright © 2014, Peter Atashian
+
+pub type Matrix3 = [[f64, ..3], ..3];
+
+pub static IDENTITY: Matrix3 = [
+    [1., 0., 0.],
+    [0., 1., 0.],
+    [0., 0., 1.],
+];
+
+pub fn diagonal(d: &[f64, ..3]) -> Matrix3 {
+    [
+        [d[0], 0., 0.],
+        [0., d[1], 0.],
+        [0., 0., d[2]],
+    ]
+}
+pub fn mul(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut m = [[0f64, ..3], ..3];
+    for i in range(0u, 3) {
+        for j in range(0u, 3) {
+            m[i][j] = a[i][0] * b[0][j] + a[i][1] * b[1][j] + a[i][2] * b[2][j];
+        }
+    }
+    m
+}
+pub fn transform(m: &Matrix3, v: &[f64, ..3]) -> [f64, ..3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+pub fn determinant(m: &Matrix3) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+pub fn invert(m: &Matrix3) -> Option<Matrix3> {
+    let det = determinant(m);
+    if det == 0. {
+        return None
+    }
+    Some([
+        [
+            (m[1][1] * m[2][2] - m[1][2] * m[2][1]) / det,
+            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) / det,
+            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) / det,
+        ],
+        [
+            (m[1][2] * m[2][0] - m[1][0] * m[2][2]) / det,
+            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) / det,
+            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) / det,
+        ],
+        [
+            (m[1][0] * m[2][1] - m[1][1] * m[2][0]) / det,
+            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) / det,
+            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) / det,
+        ],
+    ])
+}
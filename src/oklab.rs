@@ -0,0 +1,124 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use matrix::{mod, Matrix3};
+use unit::{Linear, Srgb};
+use {Color3, ColorXyz};
+
+pub static XYZ_TO_LMS: Matrix3 = [
+    [0.8189330101, 0.3618667424, -0.1288597137],
+    [0.0329845436, 0.9293118715, 0.0361456387],
+    [0.0482003018, 0.2643662691, 0.6338517070],
+];
+
+pub static LMS_TO_XYZ: Matrix3 = [
+    [1.2270138511, -0.5577999807, 0.2812561490],
+    [-0.0405801784, 1.1122568696, -0.0716766787],
+    [-0.0763812845, -0.4214819784, 1.5861632204],
+];
+
+pub static SRGB_TO_LMS: Matrix3 = [
+    [0.4122214708, 0.5363325363, 0.0514459929],
+    [0.2119034982, 0.6806995451, 0.1073969566],
+    [0.0883024619, 0.2817188376, 0.6299787005],
+];
+
+pub static LMS_TO_SRGB: Matrix3 = [
+    [4.0767416621, -3.3077115913, 0.2309699292],
+    [-1.2684380046, 2.6097574011, -0.3413193965],
+    [-0.0041960863, -0.7034186147, 1.7076147010],
+];
+
+pub static LMS_TO_LAB: Matrix3 = [
+    [0.2104542553, 0.7936177850, -0.0040720468],
+    [1.9779984951, -2.4285922050, 0.4505937099],
+    [0.0259040371, 0.7827717662, -0.8086757660],
+];
+
+pub static LAB_TO_LMS: Matrix3 = [
+    [1.0000000000, 0.3963377774, 0.2158037573],
+    [1.0000000000, -0.1055613458, -0.0638541728],
+    [1.0000000000, -0.0894841775, -1.2914855480],
+];
+
+/// Björn Ottosson's Oklab, defined on D65 XYZ and on linear sRGB.
+#[deriving(Show)]
+pub struct ColorOklab<T> {
+    pub l: T,
+    pub a: T,
+    pub b: T,
+}
+
+components!(ColorOklab { l, a, b })
+
+impl ColorOklab<f64> {
+    fn from_lms(lms: &[f64, ..3]) -> ColorOklab<f64> {
+        let lms = [lms[0].cbrt(), lms[1].cbrt(), lms[2].cbrt()];
+        let lab = matrix::transform(&LMS_TO_LAB, &lms);
+        ColorOklab {
+            l: lab[0],
+            a: lab[1],
+            b: lab[2],
+        }
+    }
+    fn to_lms(&self) -> [f64, ..3] {
+        let lms = matrix::transform(&LAB_TO_LMS, &[self.l, self.a, self.b]);
+        [lms[0].powi(3), lms[1].powi(3), lms[2].powi(3)]
+    }
+    /// Converts from linear sRGB.
+    pub fn from_rgb(c: &Color3<f64, Linear<Srgb>>) -> ColorOklab<f64> {
+        ColorOklab::from_lms(&matrix::transform(&SRGB_TO_LMS, &c.to_array()))
+    }
+    /// Converts to linear sRGB.
+    pub fn to_rgb(&self) -> Color3<f64, Linear<Srgb>> {
+        Color3::from_array(&matrix::transform(&LMS_TO_SRGB, &self.to_lms()))
+    }
+    /// Converts from XYZ relative to a D65 white with a luminance of 1.
+    pub fn from_xyz(c: &ColorXyz) -> ColorOklab<f64> {
+        ColorOklab::from_lms(&matrix::transform(&XYZ_TO_LMS, &c.to_array()))
+    }
+    pub fn to_xyz(&self) -> ColorXyz {
+        Color3::from_array(&matrix::transform(&LMS_TO_XYZ, &self.to_lms()))
+    }
+    pub fn to_oklch(&self) -> ColorOklch<f64> {
+        let (c, h) = ::to_polar(self.a, self.b);
+        ColorOklch {
+            l: self.l,
+            c: c,
+            h: h,
+        }
+    }
+}
+
+/// The cylindrical form of `ColorOklab`, with hue in degrees.
+#[deriving(Show)]
+pub struct ColorOklch<T> {
+    pub l: T,
+    pub c: T,
+    pub h: T,
+}
+
+components!(ColorOklch { l, c, h })
+
+impl ColorOklch<f64> {
+    pub fn from_rgb(c: &Color3<f64, Linear<Srgb>>) -> ColorOklch<f64> {
+        ColorOklab::from_rgb(c).to_oklch()
+    }
+    pub fn to_rgb(&self) -> Color3<f64, Linear<Srgb>> {
+        self.to_oklab().to_rgb()
+    }
+    pub fn from_xyz(c: &ColorXyz) -> ColorOklch<f64> {
+        ColorOklab::from_xyz(c).to_oklch()
+    }
+    pub fn to_xyz(&self) -> ColorXyz {
+        self.to_oklab().to_xyz()
+    }
+    pub fn to_oklab(&self) -> ColorOklab<f64> {
+        let (a, b) = ::from_polar(self.c, self.h);
+        ColorOklab {
+            l: self.l,
+            a: a,
+            b: b,
+        }
+    }
+}
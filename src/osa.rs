@@ -0,0 +1,50 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use {Color3, ColorXyz};
+
+/// The OSA-UCS lightness L with the jaune j and gréen g opponent coordinates. The conversion
+/// has no closed form inverse, so only the forward direction is provided.
+#[deriving(Show)]
+pub struct ColorOsaUcs<T> {
+    pub l: T,
+    pub j: T,
+    pub g: T,
+}
+
+components!(ColorOsaUcs { l, j, g })
+
+fn cbrt(x: f64) -> f64 {
+    x.signum() * x.abs().cbrt()
+}
+
+impl ColorOsaUcs<f64> {
+    /// Converts XYZ for the CIE 1964 10° observer, where the white has a luminance of 1.
+    ///
+    /// The chroma scaling has a pole where the cube root of Y0 is 2/3, a Y0 of 8/27 on the
+    /// 0 to 100 scale, so j and g grow without bound near it and are not finite on it.
+    pub fn from_xyz(c: &ColorXyz) -> ColorOsaUcs<f64> {
+        let Color3(x, y, z) = *c * 100.;
+        let sum = x + y + z;
+        // Black has no chromaticity, but Y0 is zero whatever K is.
+        let y0 = if sum == 0. {
+            0.
+        } else {
+            let (cx, cy) = (x / sum, y / sum);
+            let k = 4.4934 * cx * cx + 4.3034 * cy * cy - 4.276 * cx * cy - 1.3744 * cx
+                - 2.5643 * cy + 1.8103;
+            k * y
+        };
+        let d = 5.9 * (cbrt(y0) - 2. / 3.);
+        let lp = d + 5.9 * 0.042 * cbrt(y0 - 30.);
+        let scale = lp / d;
+        let r = cbrt(0.7990 * x + 0.4194 * y - 0.1648 * z);
+        let g = cbrt(-0.4493 * x + 1.3265 * y + 0.0927 * z);
+        let b = cbrt(-0.1149 * x + 0.3394 * y + 0.7170 * z);
+        ColorOsaUcs {
+            l: (lp - 14.3993) / 2f64.sqrt(),
+            j: scale * (1.7 * r + 8. * g - 9.7 * b),
+            g: scale * (-13.7 * r + 17.7 * g - 4. * b),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright © 2014, Peter Atashian
+
+/// A transfer function between linear light and an encoded signal. `encode` maps linear values
+/// to the signal and `decode` is its inverse.
+#[deriving(Show, PartialEq)]
+pub enum TransferFunction {
+    Linear,
+    /// IEC 61966-2-1.
+    Srgb,
+    /// A pure power law with the given exponent, such as 2.2, 2.4 or 2.6.
+    Gamma(f64),
+    /// The ITU-R BT.709 and BT.2020 camera OETF.
+    Rec709,
+    /// The ITU-R BT.1886 display EOTF with a zero black level.
+    Bt1886,
+    /// Adobe RGB (1998), a pure power law of 563/256.
+    AdobeRgb,
+    /// ROMM RGB as used by ProPhoto RGB.
+    ProPhoto,
+    /// SMPTE ST 2084, where a linear value of 1 is 10000 cd/m².
+    Pq,
+    /// ITU-R BT.2100 hybrid log-gamma OETF on scene light in [0, 1].
+    Hlg,
+    /// ACES S-2014-003.
+    AcesCc,
+    /// ACES S-2016-001.
+    AcesCct,
+    /// Sony S-Log3.
+    SLog3,
+    /// ARRI LogC3 at EI 800.
+    LogC3,
+    /// Panasonic V-Log.
+    VLog,
+}
+
+static PQ_M1: f64 = 2610. / 16384.;
+static PQ_M2: f64 = 2523. / 4096. * 128.;
+static PQ_C1: f64 = 3424. / 4096.;
+static PQ_C2: f64 = 2413. / 4096. * 32.;
+static PQ_C3: f64 = 2392. / 4096. * 32.;
+
+static HLG_A: f64 = 0.17883277;
+static HLG_B: f64 = 0.28466892;
+static HLG_C: f64 = 0.55991073;
+
+static LOGC_CUT: f64 = 0.010591;
+static LOGC_A: f64 = 5.555556;
+static LOGC_B: f64 = 0.052272;
+static LOGC_C: f64 = 0.247190;
+static LOGC_D: f64 = 0.385537;
+static LOGC_E: f64 = 5.367655;
+static LOGC_F: f64 = 0.092809;
+
+static VLOG_B: f64 = 0.00873;
+static VLOG_C: f64 = 0.241514;
+static VLOG_D: f64 = 0.598206;
+
+impl TransferFunction {
+    pub fn encode(&self, x: f64) -> f64 {
+        match *self {
+            TransferFunction::Linear => x,
+            TransferFunction::Srgb => {
+                if x <= 0.0031308 {
+                    x * 12.92
+                } else {
+                    x.powf(2.4f64.recip()) * (1. + 0.055) - 0.055
+                }
+            },
+            TransferFunction::Gamma(g) => x.powf(g.recip()),
+            TransferFunction::Rec709 => {
+                if x < 0.018 {
+                    x * 4.5
+                } else {
+                    x.powf(0.45) * 1.099 - 0.099
+                }
+            },
+            TransferFunction::Bt1886 => x.powf(2.4f64.recip()),
+            TransferFunction::AdobeRgb => x.powf(256. / 563.),
+            TransferFunction::ProPhoto => {
+                if x < 1. / 512. {
+                    x * 16.
+                } else {
+                    x.powf(1.8f64.recip())
+                }
+            },
+            TransferFunction::Pq => {
+                let y = x.max(0.).powf(PQ_M1);
+                ((PQ_C1 + PQ_C2 * y) / (1. + PQ_C3 * y)).powf(PQ_M2)
+            },
+            TransferFunction::Hlg => {
+                if x <= 1. / 12. {
+                    (3. * x.max(0.)).sqrt()
+                } else {
+                    HLG_A * (12. * x - HLG_B).ln() + HLG_C
+                }
+            },
+            TransferFunction::AcesCc => {
+                if x <= 0. {
+                    (-16. + 9.72) / 17.52
+                } else if x < 2f64.powi(-15) {
+                    ((2f64.powi(-16) + x * 0.5).log2() + 9.72) / 17.52
+                } else {
+                    (x.log2() + 9.72) / 17.52
+                }
+            },
+            TransferFunction::AcesCct => {
+                if x <= 0.0078125 {
+                    10.5402377416545 * x + 0.0729055341958355
+                } else {
+                    (x.log2() + 9.72) / 17.52
+                }
+            },
+            TransferFunction::SLog3 => {
+                if x >= 0.01125 {
+                    (420. + ((x + 0.01) / (0.18 + 0.01)).log10() * 261.5) / 1023.
+                } else {
+                    (x * (171.2102946929 - 95.) / 0.01125 + 95.) / 1023.
+                }
+            },
+            TransferFunction::LogC3 => {
+                if x > LOGC_CUT {
+                    LOGC_C * (LOGC_A * x + LOGC_B).log10() + LOGC_D
+                } else {
+                    LOGC_E * x + LOGC_F
+                }
+            },
+            TransferFunction::VLog => {
+                if x < 0.01 {
+                    5.6 * x + 0.125
+                } else {
+                    VLOG_C * (x + VLOG_B).log10() + VLOG_D
+                }
+            },
+        }
+    }
+    pub fn decode(&self, x: f64) -> f64 {
+        match *self {
+            TransferFunction::Linear => x,
+            TransferFunction::Srgb => {
+                if x <= 0.04045 {
+                    x / 12.92
+                } else {
+                    ((x + 0.055) / (1. + 0.055)).powf(2.4)
+                }
+            },
+            TransferFunction::Gamma(g) => x.powf(g),
+            TransferFunction::Rec709 => {
+                if x < 0.081 {
+                    x / 4.5
+                } else {
+                    ((x + 0.099) / 1.099).powf(0.45f64.recip())
+                }
+            },
+            TransferFunction::Bt1886 => x.powf(2.4),
+            TransferFunction::AdobeRgb => x.powf(563. / 256.),
+            TransferFunction::ProPhoto => {
+                if x < 16. / 512. {
+                    x / 16.
+                } else {
+                    x.powf(1.8)
+                }
+            },
+            TransferFunction::Pq => {
+                let e = x.max(0.).powf(PQ_M2.recip());
+                ((e - PQ_C1).max(0.) / (PQ_C2 - PQ_C3 * e)).powf(PQ_M1.recip())
+            },
+            TransferFunction::Hlg => {
+                if x <= 0.5 {
+                    x * x / 3.
+                } else {
+                    (((x - HLG_C) / HLG_A).exp() + HLG_B) / 12.
+                }
+            },
+            TransferFunction::AcesCc => {
+                if x < (9.72 - 15.) / 17.52 {
+                    (2f64.powf(x * 17.52 - 9.72) - 2f64.powi(-16)) * 2.
+                } else if x < (65504f64.log2() + 9.72) / 17.52 {
+                    2f64.powf(x * 17.52 - 9.72)
+                } else {
+                    65504.
+                }
+            },
+            TransferFunction::AcesCct => {
+                if x <= 0.155251141552511 {
+                    (x - 0.0729055341958355) / 10.5402377416545
+                } else {
+                    2f64.powf(x * 17.52 - 9.72)
+                }
+            },
+            TransferFunction::SLog3 => {
+                if x >= 171.2102946929 / 1023. {
+                    10f64.powf((x * 1023. - 420.) / 261.5) * (0.18 + 0.01) - 0.01
+                } else {
+                    (x * 1023. - 95.) * 0.01125 / (171.2102946929 - 95.)
+                }
+            },
+            TransferFunction::LogC3 => {
+                if x > LOGC_E * LOGC_CUT + LOGC_F {
+                    (10f64.powf((x - LOGC_D) / LOGC_C) - LOGC_B) / LOGC_A
+                } else {
+                    (x - LOGC_F) / LOGC_E
+                }
+            },
+            TransferFunction::VLog => {
+                if x < 0.181 {
+                    (x - 0.125) / 5.6
+                } else {
+                    10f64.powf((x - VLOG_D) / VLOG_C) - VLOG_B
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TransferFunction;
+
+    #[test]
+    fn round_trip() {
+        let curves = [
+            (TransferFunction::Linear, vec![]),
+            (TransferFunction::Srgb, vec![0.0031308]),
+            (TransferFunction::Gamma(2.2), vec![]),
+            (TransferFunction::Rec709, vec![0.018]),
+            (TransferFunction::Bt1886, vec![]),
+            (TransferFunction::AdobeRgb, vec![]),
+            (TransferFunction::ProPhoto, vec![1. / 512.]),
+            (TransferFunction::Pq, vec![]),
+            (TransferFunction::Hlg, vec![1. / 12.]),
+            (TransferFunction::AcesCc, vec![2f64.powi(-15)]),
+            (TransferFunction::AcesCct, vec![0.0078125]),
+            (TransferFunction::SLog3, vec![0.01125]),
+            (TransferFunction::LogC3, vec![super::LOGC_CUT]),
+            (TransferFunction::VLog, vec![0.01]),
+        ];
+        for &(ref tf, ref cuts) in curves.iter() {
+            let mut xs: Vec<f64> = range(0u, 1025).map(|i| i as f64 / 1024.).collect();
+            xs.extend(range(0i, 81).map(|k| 2f64.powf(-k as f64 / 4.)));
+            // The published constants of sRGB, LogC3 and V-Log leave their segments overlapping
+            // by a hair at the cut, so each breakpoint is crossed slightly to either side.
+            for &cut in cuts.iter() {
+                xs.push(cut * (1. - 1e-4));
+                xs.push(cut * (1. + 1e-4));
+            }
+            for &x in xs.iter() {
+                let back = tf.decode(tf.encode(x));
+                assert!((back - x).abs() <= 1e-10 * x.max(1e-6), "{} {} {}", tf, x, back);
+            }
+        }
+    }
+}
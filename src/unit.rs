@@ -0,0 +1,107 @@
+// Copyright © 2014, Peter Atashian
+
+//! Units for `Color3`, which track the color space and encoding of a color in its type so that
+//! mixing linear with encoded values, or one color space with another, does not compile. Each
+//! color model takes its input in the unit it is defined on, such as `Linear<Srgb>` for Oklab.
+
+use adaptation::AdaptationMethod;
+use matrix;
+use {Color3, ColorSpace, ColorXyz, RgbConversion};
+
+/// CIE XYZ tristimulus values.
+#[deriving(Show)]
+pub struct Xyz;
+/// Linear light RGB in the color space `S`.
+#[deriving(Show)]
+pub struct Linear<S>;
+/// RGB in the color space `S`, encoded with its transfer function.
+#[deriving(Show)]
+pub struct Encoded<S>;
+
+/// An RGB color space known at compile time.
+pub trait Space {
+    /// Takes `None` so the implementation can be picked by type alone.
+    fn color_space(_: Option<Self>) -> &'static ColorSpace;
+}
+
+/// The units that hold RGB values.
+pub trait Rgb {}
+impl<S> Rgb for Linear<S> where S: Space {}
+impl<S> Rgb for Encoded<S> where S: Space {}
+
+macro_rules! space {
+    ($name:ident, $cs:ident) => (
+        #[deriving(Show)]
+        pub struct $name;
+        impl Space for $name {
+            fn color_space(_: Option<$name>) -> &'static ColorSpace {
+                &::$cs
+            }
+        }
+    )
+}
+
+space!(Srgb, SRGB)
+space!(DisplayP3, DISPLAY_P3)
+space!(DciP3, DCI_P3)
+space!(AdobeRgb, ADOBE_RGB)
+space!(Rec709, REC709)
+space!(Rec2020, REC2020)
+space!(ProPhotoRgb, PROPHOTO_RGB)
+space!(AcesCg, ACESCG)
+space!(Aces2065_1, ACES2065_1)
+
+/// The color space of a `Space` type.
+pub fn space_of<S>() -> &'static ColorSpace where S: Space {
+    Space::color_space(None::<S>)
+}
+
+impl<S> Color3<f64, Linear<S>> where S: Space {
+    pub fn encode(&self) -> Color3<f64, Encoded<S>> {
+        let tf = space_of::<S>().transfer;
+        let &Color3(r, g, b) = self;
+        Color3(tf.encode(r), tf.encode(g), tf.encode(b))
+    }
+    /// Converts to XYZ. For many colors, build a `ColorSpaceMatrix` once and use its `to_xyz`.
+    pub fn to_xyz(&self) -> ColorXyz {
+        let m = space_of::<S>().to_xyz_matrix();
+        Color3::from_array(&matrix::transform(&m, &self.to_array()))
+    }
+    pub fn luminance(&self) -> f64 {
+        let cs = space_of::<S>();
+        let &Color3(r, g, b) = self;
+        r * cs.r.Y + g * cs.g.Y + b * cs.b.Y
+    }
+    /// Darkens toward black, or lightens toward white, until the luminance is `lum`.
+    pub fn target_luminance(&self, lum: f64) -> Color3<f64, Linear<S>> {
+        let l = self.luminance();
+        if l < lum {
+            let d = (lum - 1.) / (l - 1.);
+            *self * d + Color3::white() * (1. - d)
+        } else {
+            *self * (lum / l)
+        }
+    }
+    /// Converts to another color space, adapting between their white points. For many colors,
+    /// build an `RgbConversion` once and use its `convert`.
+    pub fn convert<D>(&self, method: AdaptationMethod) -> Color3<f64, Linear<D>> where D: Space {
+        RgbConversion::new(method).convert(self)
+    }
+}
+
+impl<S> Color3<f64, Encoded<S>> where S: Space {
+    pub fn decode(&self) -> Color3<f64, Linear<S>> {
+        let tf = space_of::<S>().transfer;
+        let &Color3(r, g, b) = self;
+        Color3(tf.decode(r), tf.decode(g), tf.decode(b))
+    }
+}
+
+impl Color3<f64, Xyz> {
+    /// Converts to linear RGB. For many colors, build a `ColorSpaceMatrix` once and use its
+    /// `to_rgb`.
+    pub fn to_linear<S>(&self) -> Color3<f64, Linear<S>> where S: Space {
+        let m = space_of::<S>().to_rgb_matrix();
+        Color3::from_array(&matrix::transform(&m, &self.to_array()))
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright © 2014, Peter Atashian
+
+use std::cmp;
+use std::io::{mod, IoError, IoResult};
+use frame::{ChromaFormat, ChromaSiting, Frame, Plane};
+
+/// The stream header of an uncompressed YUV4MPEG2 file.
+#[deriving(Show, Clone, PartialEq)]
+pub struct Y4mHeader {
+    pub width: uint,
+    pub height: uint,
+    pub framerate: (uint, uint),
+    pub aspect: (uint, uint),
+    /// `p` for progressive, `t` or `b` for top or bottom field first, or `m` for mixed.
+    pub interlace: char,
+    pub format: ChromaFormat,
+    pub siting: ChromaSiting,
+    pub bit_depth: uint,
+}
+
+impl Y4mHeader {
+    pub fn new(width: uint, height: uint, format: ChromaFormat, siting: ChromaSiting,
+               bit_depth: uint) -> Y4mHeader {
+        Y4mHeader {
+            width: width,
+            height: height,
+            framerate: (25, 1),
+            aspect: (1, 1),
+            interlace: 'p',
+            format: format,
+            siting: siting,
+            bit_depth: bit_depth,
+        }
+    }
+    /// The `C` tag, followed by a chroma location extension when the tag alone would be read
+    /// back with a different siting.
+    fn colorspace(&self) -> String {
+        let tag = self.colorspace_tag();
+        match parse_colorspace(tag.as_slice()) {
+            Ok((_, siting, _)) if siting == self.siting => tag,
+            _ => format!("{} XCHROMALOC={}", tag, chroma_location(self.siting)),
+        }
+    }
+    fn colorspace_tag(&self) -> String {
+        let base = match self.format {
+            ChromaFormat::Yuv444 => "444",
+            ChromaFormat::Yuv422 => "422",
+            ChromaFormat::Yuv420 => "420",
+        };
+        if self.bit_depth > 8 {
+            format!("{}p{}", base, self.bit_depth)
+        } else if self.format == ChromaFormat::Yuv420 {
+            match self.siting {
+                ChromaSiting::Center => "420jpeg".to_string(),
+                ChromaSiting::Left => "420mpeg2".to_string(),
+                ChromaSiting::TopLeft => "420paldv".to_string(),
+            }
+        } else {
+            base.to_string()
+        }
+    }
+}
+
+fn invalid(detail: String) -> IoError {
+    IoError {
+        kind: io::InvalidInput,
+        desc: "invalid y4m stream",
+        detail: Some(detail),
+    }
+}
+
+fn chroma_location(siting: ChromaSiting) -> &'static str {
+    match siting {
+        ChromaSiting::Center => "center",
+        ChromaSiting::Left => "left",
+        ChromaSiting::TopLeft => "topleft",
+    }
+}
+
+fn parse_chroma_location(s: &str) -> IoResult<ChromaSiting> {
+    Ok(match s {
+        "center" => ChromaSiting::Center,
+        "left" => ChromaSiting::Left,
+        "topleft" => ChromaSiting::TopLeft,
+        _ => return Err(invalid(format!("unsupported chroma location {}", s))),
+    })
+}
+
+fn parse_ratio(s: &str) -> IoResult<(uint, uint)> {
+    let mut parts = s.split(':').map(|p| from_str::<uint>(p));
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Some(n)), Some(Some(d)), None) => Ok((n, d)),
+        _ => Err(invalid(format!("bad ratio {}", s))),
+    }
+}
+
+fn parse_colorspace(s: &str) -> IoResult<(ChromaFormat, ChromaSiting, uint)> {
+    Ok(match s {
+        "420" | "420jpeg" => (ChromaFormat::Yuv420, ChromaSiting::Center, 8),
+        "420mpeg2" => (ChromaFormat::Yuv420, ChromaSiting::Left, 8),
+        "420paldv" => (ChromaFormat::Yuv420, ChromaSiting::TopLeft, 8),
+        "422" => (ChromaFormat::Yuv422, ChromaSiting::Left, 8),
+        "444" => (ChromaFormat::Yuv444, ChromaSiting::Center, 8),
+        "420p10" => (ChromaFormat::Yuv420, ChromaSiting::Left, 10),
+        "422p10" => (ChromaFormat::Yuv422, ChromaSiting::Left, 10),
+        "444p10" => (ChromaFormat::Yuv444, ChromaSiting::Center, 10),
+        "420p12" => (ChromaFormat::Yuv420, ChromaSiting::Left, 12),
+        "422p12" => (ChromaFormat::Yuv422, ChromaSiting::Left, 12),
+        "444p12" => (ChromaFormat::Yuv444, ChromaSiting::Center, 12),
+        _ => return Err(invalid(format!("unsupported colorspace {}", s))),
+    })
+}
+
+pub struct Y4mReader<R> {
+    reader: R,
+    pub header: Y4mHeader,
+}
+
+impl<R> Y4mReader<R> where R: Buffer {
+    pub fn new(mut reader: R) -> IoResult<Y4mReader<R>> {
+        let line = try!(reader.read_line());
+        let mut tokens = line.as_slice().trim_right().split(' ');
+        if tokens.next() != Some("YUV4MPEG2") {
+            return Err(invalid("missing YUV4MPEG2 signature".to_string()))
+        }
+        let mut header = Y4mHeader::new(0, 0, ChromaFormat::Yuv420, ChromaSiting::Center, 8);
+        let mut location = None;
+        for token in tokens.filter(|t| !t.is_empty()) {
+            let value = token.slice_from(1);
+            match token.char_at(0) {
+                'W' => header.width = try!(from_str(value).ok_or(invalid(token.to_string()))),
+                'H' => header.height = try!(from_str(value).ok_or(invalid(token.to_string()))),
+                'F' => header.framerate = try!(parse_ratio(value)),
+                'A' => header.aspect = try!(parse_ratio(value)),
+                'I' if value.len() == 1 => header.interlace = value.char_at(0),
+                'I' => return Err(invalid(token.to_string())),
+                'C' => {
+                    let (format, siting, bit_depth) = try!(parse_colorspace(value));
+                    header.format = format;
+                    header.siting = siting;
+                    header.bit_depth = bit_depth;
+                },
+                'X' if value.starts_with("CHROMALOC=") => {
+                    location = Some(try!(parse_chroma_location(value.slice_from(10))));
+                },
+                // Other extension parameters carry nothing this reader needs.
+                _ => (),
+            }
+        }
+        // The extension may come before the colorspace, which implies a siting of its own.
+        match location {
+            Some(siting) => header.siting = siting,
+            None => (),
+        }
+        if header.width == 0 || header.height == 0 {
+            return Err(invalid("missing frame size".to_string()))
+        }
+        Ok(Y4mReader {
+            reader: reader,
+            header: header,
+        })
+    }
+    fn read_plane(&mut self, width: uint, height: uint) -> IoResult<Plane> {
+        let wide = self.header.bit_depth > 8;
+        let bytes = try!(self.reader.read_exact(width * height * if wide { 2 } else { 1 }));
+        let data = if wide {
+            bytes.as_slice().chunks(2).map(|b| b[0] as u16 | (b[1] as u16 << 8)).collect()
+        } else {
+            bytes.iter().map(|&b| b as u16).collect()
+        };
+        Ok(Plane {
+            width: width,
+            height: height,
+            data: data,
+        })
+    }
+    /// Reads the next frame, or `None` at the end of the stream.
+    pub fn read_frame(&mut self) -> IoResult<Option<Frame>> {
+        let line = match self.reader.read_line() {
+            Ok(line) => line,
+            Err(ref e) if e.kind == io::EndOfFile => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if !line.as_slice().starts_with("FRAME") {
+            return Err(invalid("missing FRAME marker".to_string()))
+        }
+        let (width, height) = (self.header.width, self.header.height);
+        let (cw, ch) = self.header.format.chroma_size(width, height);
+        let y = try!(self.read_plane(width, height));
+        let cb = try!(self.read_plane(cw, ch));
+        let cr = try!(self.read_plane(cw, ch));
+        Ok(Some(Frame {
+            width: width,
+            height: height,
+            format: self.header.format,
+            bit_depth: self.header.bit_depth,
+            y: y,
+            cb: cb,
+            cr: cr,
+        }))
+    }
+}
+
+pub struct Y4mWriter<W> {
+    writer: W,
+    pub header: Y4mHeader,
+}
+
+impl<W> Y4mWriter<W> where W: Writer {
+    /// Fails when the header has a bit depth that no colorspace tag can describe.
+    pub fn new(mut writer: W, header: Y4mHeader) -> IoResult<Y4mWriter<W>> {
+        match parse_colorspace(header.colorspace_tag().as_slice()) {
+            Ok((_, _, bit_depth)) if bit_depth == header.bit_depth => (),
+            _ => return Err(invalid(format!("unsupported bit depth {}", header.bit_depth))),
+        }
+        let ((fn_, fd), (an, ad)) = (header.framerate, header.aspect);
+        try!(write!(&mut writer, "YUV4MPEG2 W{} H{} F{}:{} I{} A{}:{} C{}\n",
+                    header.width, header.height, fn_, fd, header.interlace, an, ad,
+                    header.colorspace()));
+        Ok(Y4mWriter {
+            writer: writer,
+            header: header,
+        })
+    }
+    /// Writes code values, clamping any that are out of range for the bit depth.
+    fn write_plane(&mut self, plane: &Plane) -> IoResult<()> {
+        let max = (1u16 << self.header.bit_depth) - 1;
+        let data = plane.data.iter().map(|&v| cmp::min(v, max));
+        if self.header.bit_depth > 8 {
+            let mut bytes = Vec::with_capacity(plane.data.len() * 2);
+            for v in data {
+                bytes.push(v as u8);
+                bytes.push((v >> 8) as u8);
+            }
+            self.writer.write(bytes.as_slice())
+        } else {
+            let bytes: Vec<u8> = data.map(|v| v as u8).collect();
+            self.writer.write(bytes.as_slice())
+        }
+    }
+    /// Writes a frame, which must match the size, chroma format and bit depth of the header.
+    pub fn write_frame(&mut self, frame: &Frame) -> IoResult<()> {
+        if frame.width != self.header.width || frame.height != self.header.height
+            || frame.format != self.header.format || frame.bit_depth != self.header.bit_depth {
+            return Err(invalid("frame does not match the stream header".to_string()))
+        }
+        try!(self.writer.write(b"FRAME\n"));
+        try!(self.write_plane(&frame.y));
+        try!(self.write_plane(&frame.cb));
+        self.write_plane(&frame.cr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufReader, MemWriter};
+    use frame::{ChromaFormat, ChromaSiting, Frame};
+    use super::{Y4mHeader, Y4mReader, Y4mWriter};
+
+    #[test]
+    fn round_trip() {
+        let mut frame = Frame::new(4, 2, ChromaFormat::Yuv420, 10);
+        for (i, v) in frame.y.data.iter_mut().enumerate() {
+            *v = (i * 100) as u16;
+        }
+        frame.cb.data = vec![64, 1023];
+        frame.cr.data = vec![512, 940];
+        let header = Y4mHeader::new(4, 2, ChromaFormat::Yuv420, ChromaSiting::Center, 10);
+        let mut writer = Y4mWriter::new(MemWriter::new(), header.clone()).unwrap();
+        writer.write_frame(&frame).unwrap();
+        writer.write_frame(&frame).unwrap();
+        let bytes = writer.writer.unwrap();
+        let mut reader = Y4mReader::new(BufReader::new(bytes.as_slice())).unwrap();
+        assert_eq!(reader.header, header);
+        assert_eq!(reader.read_frame().unwrap(), Some(frame.clone()));
+        assert_eq!(reader.read_frame().unwrap(), Some(frame));
+        assert_eq!(reader.read_frame().unwrap(), None);
+    }
+
+    #[test]
+    fn unsupported_depth() {
+        for &bit_depth in [6u, 9, 16].iter() {
+            let header = Y4mHeader::new(2, 2, ChromaFormat::Yuv444, ChromaSiting::Center,
+                                        bit_depth);
+            assert!(Y4mWriter::new(MemWriter::new(), header).is_err());
+        }
+    }
+
+    #[test]
+    fn bare_interlace() {
+        let header = b"YUV4MPEG2 W2 H2 I C444\n";
+        assert!(Y4mReader::new(BufReader::new(header)).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright © 2014, Peter Atashian
+
+use component::Component;
+use transfer::TransferFunction;
+use unit::{mod, Encoded, Linear, Rec2020, Space};
+use {Color3, ColorSpace};
+
+/// The weights of red and blue in luma. The weight of green makes up the rest.
+#[deriving(Show, PartialEq)]
+pub struct LumaCoefficients {
+    pub kr: f64,
+    pub kb: f64,
+}
+
+pub static BT601: LumaCoefficients = LumaCoefficients { kr: 0.299, kb: 0.114 };
+pub static BT709: LumaCoefficients = LumaCoefficients { kr: 0.2126, kb: 0.0722 };
+pub static BT2020: LumaCoefficients = LumaCoefficients { kr: 0.2627, kb: 0.0593 };
+
+impl LumaCoefficients {
+    /// Uses the luminance of the red and blue primaries of a color space.
+    pub fn from_color_space(cs: &ColorSpace) -> LumaCoefficients {
+        LumaCoefficients {
+            kr: cs.r.Y,
+            kb: cs.b.Y,
+        }
+    }
+    pub fn kg(&self) -> f64 {
+        1. - self.kr - self.kb
+    }
+}
+
+#[deriving(Show, PartialEq)]
+pub enum Range {
+    /// Uses every code value, with zero chroma at the middle code.
+    Full,
+    /// Luma from 16 to 235 and chroma from 16 to 240, scaled up for deeper bit depths.
+    Limited,
+}
+
+/// A bit depth outside 8 to 16, which code values of this module cannot represent.
+#[deriving(Show, PartialEq)]
+pub struct UnsupportedBitDepth(pub uint);
+
+fn check_bits(bits: uint) -> Result<(), UnsupportedBitDepth> {
+    if bits < 8 || bits > 16 {
+        Err(UnsupportedBitDepth(bits))
+    } else {
+        Ok(())
+    }
+}
+
+/// Luma in [0, 1] and chroma in [-0.5, 0.5], computed from encoded RGB.
+#[deriving(Show)]
+pub struct ColorYcbcr<T> {
+    pub y: T,
+    pub cb: T,
+    pub cr: T,
+}
+
+components!(ColorYcbcr { y, cb, cr })
+
+impl ColorYcbcr<f64> {
+    pub fn from_rgb<S>(c: &Color3<f64, Encoded<S>>, k: &LumaCoefficients) -> ColorYcbcr<f64>
+                       where S: Space {
+        let &Color3(r, g, b) = c;
+        let y = k.kr * r + k.kg() * g + k.kb * b;
+        ColorYcbcr {
+            y: y,
+            cb: (b - y) / (2. * (1. - k.kb)),
+            cr: (r - y) / (2. * (1. - k.kr)),
+        }
+    }
+    pub fn to_rgb<S>(&self, k: &LumaCoefficients) -> Color3<f64, Encoded<S>> where S: Space {
+        let r = self.y + 2. * (1. - k.kr) * self.cr;
+        let b = self.y + 2. * (1. - k.kb) * self.cb;
+        Color3(r, (self.y - k.kr * r - k.kb * b) / k.kg(), b)
+    }
+    /// The BT.2020 constant luminance Yc'Cbc'Crc' from linear Rec.2020 RGB, where luminance is
+    /// computed before the transfer function rather than after it.
+    pub fn from_rec2020_constant_luminance(c: &Color3<f64, Linear<Rec2020>>) -> ColorYcbcr<f64> {
+        let tf = TransferFunction::Rec709;
+        let &Color3(r, g, b) = c;
+        let y = tf.encode(BT2020.kr * r + BT2020.kg() * g + BT2020.kb * b);
+        let db = tf.encode(b) - y;
+        let dr = tf.encode(r) - y;
+        ColorYcbcr {
+            y: y,
+            cb: if db <= 0. { db / 1.9404 } else { db / 1.5816 },
+            cr: if dr <= 0. { dr / 1.7184 } else { dr / 0.9936 },
+        }
+    }
+    pub fn to_rec2020_constant_luminance(&self) -> Color3<f64, Linear<Rec2020>> {
+        let tf = TransferFunction::Rec709;
+        let b = self.y + if self.cb <= 0. { self.cb * 1.9404 } else { self.cb * 1.5816 };
+        let r = self.y + if self.cr <= 0. { self.cr * 1.7184 } else { self.cr * 0.9936 };
+        let (y, r, b) = (tf.decode(self.y), tf.decode(r), tf.decode(b));
+        Color3(r, (y - BT2020.kr * r - BT2020.kb * b) / BT2020.kg(), b)
+    }
+    /// Quantizes to code values with the given bit depth from 8 to 16, such as 8, 10 or 12.
+    pub fn quantize(&self, range: Range, bits: uint)
+                    -> Result<ColorYcbcrInt, UnsupportedBitDepth> {
+        try!(check_bits(bits));
+        let max = ((1u << bits) - 1) as f64;
+        let q = |x: f64| x.round().min(max).max(0.) as u16;
+        Ok(match range {
+            Range::Full => {
+                let mid = (1u << (bits - 1)) as f64;
+                ColorYcbcrInt {
+                    y: q(self.y * max),
+                    cb: q(self.cb * max + mid),
+                    cr: q(self.cr * max + mid),
+                }
+            },
+            Range::Limited => {
+                let scale = (1u << (bits - 8)) as f64;
+                ColorYcbcrInt {
+                    y: q((219. * self.y + 16.) * scale),
+                    cb: q((224. * self.cb + 128.) * scale),
+                    cr: q((224. * self.cr + 128.) * scale),
+                }
+            },
+        })
+    }
+}
+
+/// YCbCr code values for bit depths of up to 16.
+#[deriving(Show, PartialEq)]
+pub struct ColorYcbcrInt {
+    pub y: u16,
+    pub cb: u16,
+    pub cr: u16,
+}
+
+impl ColorYcbcrInt {
+    /// Quantizes encoded RGB of any component type, weighing luma by the primaries of its
+    /// color space.
+    pub fn from_color3<T, S>(c: &Color3<T, Encoded<S>>, range: Range, bits: uint)
+                             -> Result<ColorYcbcrInt, UnsupportedBitDepth>
+                             where T: Component, S: Space {
+        let k = LumaCoefficients::from_color_space(unit::space_of::<S>());
+        ColorYcbcr::from_rgb(&c.cast::<f64>(), &k).quantize(range, bits)
+    }
+    pub fn to_color3<T, S>(&self, range: Range, bits: uint)
+                           -> Result<Color3<T, Encoded<S>>, UnsupportedBitDepth>
+                           where T: Component, S: Space {
+        let k = LumaCoefficients::from_color_space(unit::space_of::<S>());
+        Ok(try!(self.dequantize(range, bits)).to_rgb::<S>(&k).cast())
+    }
+    pub fn dequantize(&self, range: Range, bits: uint)
+                      -> Result<ColorYcbcr<f64>, UnsupportedBitDepth> {
+        try!(check_bits(bits));
+        let (y, cb, cr) = (self.y as f64, self.cb as f64, self.cr as f64);
+        Ok(match range {
+            Range::Full => {
+                let max = ((1u << bits) - 1) as f64;
+                let mid = (1u << (bits - 1)) as f64;
+                ColorYcbcr {
+                    y: y / max,
+                    cb: (cb - mid) / max,
+                    cr: (cr - mid) / max,
+                }
+            },
+            Range::Limited => {
+                let scale = (1u << (bits - 8)) as f64;
+                ColorYcbcr {
+                    y: (y / scale - 16.) / 219.,
+                    cb: (cb / scale - 128.) / 224.,
+                    cr: (cr / scale - 128.) / 224.,
+                }
+            },
+        })
+    }
+}